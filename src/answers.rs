@@ -0,0 +1,136 @@
+//! Known-correct answers for our own inputs, so that changes to shared code
+//! can be checked against real inputs and not just the samples.
+//!
+//! The registry file has one answer per line: day, part, input hash and
+//! answer separated by tabs, with backslashes, tabs and newlines in the
+//! answer escaped.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use crate::days::Part;
+
+/// FNV-1a over the input's lines, so line endings and a trailing newline
+/// don't change it.
+pub fn input_hash(input: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (i, line) in input.lines().enumerate() {
+        let sep: &[u8] = if i == 0 { b"" } else { b"\n" };
+        for b in sep.iter().chain(line.as_bytes()) {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{hash:016x}")
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Registry {
+    answers: BTreeMap<(u32, Part, String), String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+impl Registry {
+    pub fn get(&self, day: u32, part: Part, hash: &str) -> Option<&str> {
+        self.answers.get(&(day, part, hash.to_string())).map(String::as_str)
+    }
+    pub fn insert(&mut self, day: u32, part: Part, hash: &str, answer: &str) {
+        self.answers.insert((day, part, hash.to_string()), answer.to_string());
+    }
+    pub fn len(&self) -> usize { self.answers.len() }
+    pub fn is_empty(&self) -> bool { self.answers.is_empty() }
+
+    pub fn check(&self, day: u32, part: Part, hash: &str, answer: &str) -> Check {
+        match self.get(day, part, hash) {
+            Some(expected) if expected == answer => Check::Correct,
+            Some(expected) => Check::Wrong { expected: expected.to_string() },
+            None => Check::Unknown,
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            _ => return Err(format!("bad escape in {s}")),
+        }
+    }
+    Ok(out)
+}
+
+impl FromStr for Registry {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut registry = Registry::default();
+        for (n, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty() && !l.starts_with('#')) {
+            let err = |e: String| format!("line {}: {e}", n + 1);
+            let fields: Vec<&str> = line.splitn(4, '\t').collect();
+            let [day, part, hash, answer] = fields[..] else {
+                return Err(err(format!("expected day, part, hash and answer: {line}")));
+            };
+            let day = day.trim_start_matches("day").parse().map_err(|_| err(format!("invalid day: {day}")))?;
+            let part = part.trim_start_matches("part").parse().map_err(err)?;
+            registry.insert(day, part, hash, &unescape(answer).map_err(err)?);
+        }
+        Ok(registry)
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, part, hash), answer) in &self.answers {
+            writeln!(f, "day{day:02}\tpart{part}\t{hash}\t{}", escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry() {
+        assert_eq!(input_hash("a\nb\n"), input_hash("a\r\nb"));
+        assert_ne!(input_hash("a\nb\n"), input_hash("ab\n"));
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+
+        let hash = input_hash("+1\n-2\n");
+        let mut reg = Registry::default();
+        reg.insert(1, Part::One, &hash, "-1");
+        reg.insert(10, Part::One, &hash, "#..#\n#\t\\#");
+        reg.insert(1, Part::Two, "0000000000000000", "42");
+        let text = reg.to_string();
+        assert_eq!(text.lines().next(), Some(format!("day01\tpart1\t{hash}\t-1").as_str()));
+        assert_eq!(text.lines().count(), 3);
+        assert_eq!(text.parse::<Registry>(), Ok(reg.clone()));
+
+        assert_eq!(reg.check(1, Part::One, &hash, "-1"), Check::Correct);
+        assert_eq!(reg.check(1, Part::One, &hash, "7"), Check::Wrong { expected: "-1".into() });
+        assert_eq!(reg.check(1, Part::Two, &hash, "42"), Check::Unknown);
+        assert_eq!(reg.get(10, Part::One, &hash), Some("#..#\n#\t\\#"));
+
+        assert!("# comment\n\nday01\tpart1\tabc\t5\n".parse::<Registry>().is_ok());
+        assert!("day01\tpart3\tabc\t5\n".parse::<Registry>().is_err());
+        assert!("day01 part1 abc 5\n".parse::<Registry>().is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! One-dimensional cellular automata over `NumberLine`s, with any
+//! neighbourhood radius and any cell type. Long runs are extrapolated: once
+//! the pattern repeats (possibly shifted) the rest of the run is known, so
+//! asking for generation 50 billion only simulates until the first repeat.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::str::FromStr;
+use std::vec::Vec;
+use crate::number_line::{NumberLine, Pattern};
+
+pub mod hashlife;
+
+/// What a neighbourhood with no rule listed for it turns into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unlisted<T> {
+    Value(T),
+    /// The centre cell stays as it is.
+    Keep,
+}
+
+pub struct Rule<T: Copy + Eq + Hash> {
+    radius: usize,
+    table: HashMap<Vec<T>, T>,
+    unlisted: Unlisted<T>,
+}
+
+/// How a pattern keeps recurring: from generation `start` on, every
+/// `period` generations the same pattern comes back moved by `shift`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+    pub shift: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleKind {
+    FixedPoint,
+    Oscillator,
+    Glider,
+}
+
+impl Cycle {
+    pub fn kind(&self) -> CycleKind {
+        match (self.period, self.shift) {
+            (_, s) if s != 0 => CycleKind::Glider,
+            (1, _) => CycleKind::FixedPoint,
+            _ => CycleKind::Oscillator,
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash> Rule<T> {
+    pub fn new(radius: usize, unlisted: Unlisted<T>) -> Self {
+        Self {
+            radius,
+            table: HashMap::new(),
+            unlisted,
+        }
+    }
+    pub fn radius(&self) -> usize { self.radius }
+    /// Adds a rule; `neighbourhood` runs left to right and must be
+    /// `2 * radius + 1` cells long.
+    pub fn insert(&mut self, neighbourhood: &[T], result: T) {
+        assert_eq!(neighbourhood.len(), 2 * self.radius + 1);
+        self.table.insert(neighbourhood.to_vec(), result);
+    }
+    pub fn apply(&self, neighbourhood: &[T]) -> T {
+        match (self.table.get(neighbourhood), self.unlisted) {
+            (Some(v), _) => *v,
+            (None, Unlisted::Value(v)) => v,
+            (None, Unlisted::Keep) => neighbourhood[self.radius],
+        }
+    }
+
+    /// The next generation. A neighbourhood of nothing but the line's
+    /// default must give the default again, or the line would be infinite.
+    pub fn step(&self, line: &NumberLine<T>) -> NumberLine<T> {
+        let default_val = line.default_val();
+        assert!(self.apply(&vec![default_val; 2 * self.radius + 1]) == default_val,
+            "rule must map the empty neighbourhood to the default");
+        let r = self.radius as i64;
+        let mut next = NumberLine::from_initial(&[], default_val);
+        next.extend(line.windows(2 * self.radius + 1).map(|(start, w)| (start + r, self.apply(&w))));
+        next
+    }
+
+    /// Runs until the pattern repeats, up to `max_gens` generations.
+    pub fn find_cycle(&self, line: &NumberLine<T>, max_gens: usize) -> Option<Cycle> {
+        let mut history = History::default();
+        let mut line = line.clone();
+        for gen in 0 ..= max_gens {
+            if let Some(cycle) = history.push(&line, gen) {
+                return Some(cycle);
+            }
+            line = self.step(&line);
+        }
+        None
+    }
+
+    /// The line after `gens` generations, extrapolated from the first
+    /// repeat if there is one.
+    pub fn nth(&self, line: &NumberLine<T>, gens: usize) -> NumberLine<T> {
+        let mut history = History::default();
+        let mut line = line.clone();
+        for gen in 0 .. gens {
+            if let Some(cycle) = history.push(&line, gen) {
+                let laps = (gens - cycle.start) / cycle.period;
+                let (pattern, offset) = &history.states[cycle.start + (gens - cycle.start) % cycle.period];
+                return pattern.to_line(offset + laps as i64 * cycle.shift);
+            }
+            line = self.step(&line);
+        }
+        line
+    }
+}
+
+/// Rules in day12's format, one `..#.# => #` per line. Unlisted
+/// neighbourhoods die.
+impl FromStr for Rule<bool> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cell = |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(format!("invalid cell: {c}")),
+        };
+        let mut rule: Option<Rule<bool>> = None;
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let Some((from, to)) = line.split_once(" => ") else {
+                return Err(format!("invalid rule: {line}"));
+            };
+            let from = from.chars().map(cell).collect::<Result<Vec<_>, _>>()?;
+            let to = match to.chars().collect::<Vec<_>>()[..] {
+                [c] => cell(c)?,
+                _ => return Err(format!("invalid rule: {line}")),
+            };
+            let rule = rule.get_or_insert_with(|| Rule::new(from.len() / 2, Unlisted::Value(false)));
+            if from.len() != 2 * rule.radius + 1 {
+                return Err(format!("neighbourhood has the wrong width: {line}"));
+            }
+            rule.insert(&from, to);
+        }
+        rule.ok_or_else(|| "no rules".to_string())
+    }
+}
+
+// Every generation's canonical form so far, and where each pattern first
+// turned up.
+struct History<T> {
+    states: Vec<(Pattern<T>, i64)>,
+    seen: HashMap<Pattern<T>, usize>,
+}
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self { states: Vec::new(), seen: HashMap::new() }
+    }
+}
+impl<T: Copy + Eq + Hash> History<T> {
+    fn push(&mut self, line: &NumberLine<T>, gen: usize) -> Option<Cycle> {
+        let (pattern, offset) = line.canonical();
+        if let Some(start) = self.seen.get(&pattern) {
+            return Some(Cycle {
+                start: *start,
+                period: gen - start,
+                shift: offset - self.states[*start].1,
+            });
+        }
+        self.seen.insert(pattern.clone(), gen);
+        self.states.push((pattern, offset));
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cycles() {
+        let single = NumberLine::from_initial(&[1u8], 0);
+
+        let mut still = Rule::new(1, Unlisted::Keep);
+        still.insert(&[0, 0, 0], 0);
+        assert_eq!(still.find_cycle(&single, 10).unwrap().kind(), CycleKind::FixedPoint);
+
+        let mut blinker = Rule::new(1, Unlisted::Value(0));
+        blinker.insert(&[0, 1, 0], 2);
+        blinker.insert(&[0, 2, 0], 1);
+        let cycle = blinker.find_cycle(&single, 10).unwrap();
+        assert_eq!(cycle, Cycle { start: 0, period: 2, shift: 0 });
+        assert_eq!(cycle.kind(), CycleKind::Oscillator);
+        assert_eq!(blinker.nth(&single, 1001)[0], 2);
+
+        // moves one cell right every other generation
+        let mut glider = Rule::new(1, Unlisted::Value(0));
+        glider.insert(&[1, 0, 0], 2);
+        glider.insert(&[0, 2, 0], 1);
+        let cycle = glider.find_cycle(&single, 10).unwrap();
+        assert_eq!(cycle, Cycle { start: 0, period: 2, shift: 1 });
+        assert_eq!(cycle.kind(), CycleKind::Glider);
+        let far = glider.nth(&single, 1_000_000_001);
+        assert_eq!(far.enumerate().collect::<Vec<_>>(), vec![(500_000_001, 2)]);
+
+        let mut dies = Rule::new(2, Unlisted::Value(0));
+        dies.insert(&[0, 0, 1, 0, 0], 0);
+        assert_eq!(dies.nth(&single, 50_000_000_000).enumerate().next(), None);
+    }
+}
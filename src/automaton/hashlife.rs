@@ -0,0 +1,319 @@
+//! Hashlife for one-dimensional boolean automata. The line is a binary tree
+//! of hash-consed nodes, and the result of advancing each node's centre by a
+//! power-of-two number of generations is memoized, so patterns that keep
+//! changing without ever repeating can still be run for billions of
+//! generations as long as they have enough regular structure.
+//!
+//! A node of level k covers 2^k cells. Its result after 2^j generations is
+//! its centre half, which can't be influenced from outside the node while
+//! `2^j * span <= 2^(k-2)`, `span` being the rule's radius rounded up to a
+//! power of two.
+
+use std::collections::HashMap;
+use std::vec::Vec;
+use super::Rule;
+use crate::number_line::NumberLine;
+
+type Id = usize;
+const DEAD: Id = 0;
+const LIVE: Id = 1;
+// Positions within a node have to fit in an i64 as well as a u64.
+const MAX_LEVEL: u32 = 62;
+
+/// The most generations `advance` and `evolve` will take in one call. The
+/// universe grows to cover everything the rule could reach in that time, and
+/// past this its positions would no longer fit in an `i64`.
+pub const MAX_GENS: u64 = 1 << 48;
+
+struct Node {
+    children: (Id, Id),
+    level: u32,
+    pop: u64,
+    /// Sum of the live cells' positions within the node.
+    sum: u128,
+}
+
+/// A line loaded into a `Hashlife`: its root node and the index of the
+/// root's leftmost cell. Only meaningful with the `Hashlife` that made it.
+#[derive(Clone, Copy, Debug)]
+pub struct Universe {
+    root: Id,
+    origin: i64,
+}
+
+pub struct Hashlife {
+    radius: usize,
+    rho: u32,
+    /// Next state for each neighbourhood, leftmost cell in the top bit.
+    table: Vec<bool>,
+    nodes: Vec<Node>,
+    interned: HashMap<(Id, Id), Id>,
+    empty: Vec<Id>,
+    results: HashMap<(Id, u32), Id>,
+}
+
+impl Hashlife {
+    pub fn new(rule: &Rule<bool>) -> Self {
+        let radius = rule.radius();
+        let width = 2 * radius + 1;
+        let table: Vec<bool> = (0 .. 1usize << width).map(|code| {
+            let cells: Vec<bool> = (0..width).map(|i| code >> (width - 1 - i) & 1 == 1).collect();
+            rule.apply(&cells)
+        }).collect();
+        assert!(!table[0], "rule must keep empty space empty");
+        let leaf = |pop| Node { children: (DEAD, DEAD), level: 0, pop, sum: 0 };
+        Self {
+            radius,
+            rho: radius.max(1).next_power_of_two().trailing_zeros(),
+            table,
+            nodes: vec![leaf(0), leaf(1)],
+            interned: HashMap::new(),
+            empty: vec![DEAD],
+            results: HashMap::new(),
+        }
+    }
+
+    fn join(&mut self, a: Id, b: Id) -> Id {
+        if let Some(id) = self.interned.get(&(a, b)) {
+            return *id;
+        }
+        let (na, nb) = (&self.nodes[a], &self.nodes[b]);
+        let node = Node {
+            children: (a, b),
+            level: na.level + 1,
+            pop: na.pop + nb.pop,
+            sum: na.sum + nb.sum + ((nb.pop as u128) << na.level),
+        };
+        self.nodes.push(node);
+        self.interned.insert((a, b), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+    fn empty(&mut self, level: u32) -> Id {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let bigger = self.join(e, e);
+            self.empty.push(bigger);
+        }
+        self.empty[level as usize]
+    }
+    // `cells.len()` must be a power of two.
+    fn build(&mut self, cells: &[bool]) -> Id {
+        if cells.len() == 1 {
+            return if cells[0] { LIVE } else { DEAD };
+        }
+        let (a, b) = cells.split_at(cells.len() / 2);
+        let (a, b) = (self.build(a), self.build(b));
+        self.join(a, b)
+    }
+    fn cells(&self, id: Id, out: &mut Vec<bool>) {
+        match self.nodes[id].level {
+            0 => out.push(id == LIVE),
+            _ => {
+                let (a, b) = self.nodes[id].children;
+                self.cells(a, out);
+                self.cells(b, out);
+            },
+        }
+    }
+    fn centre(&mut self, id: Id) -> Id {
+        let (a, b) = self.nodes[id].children;
+        self.join(self.nodes[a].children.1, self.nodes[b].children.0)
+    }
+    // One generation of the smallest node that has a result, done directly.
+    fn base(&mut self, id: Id) -> Id {
+        let mut cells = Vec::new();
+        self.cells(id, &mut cells);
+        let n = cells.len();
+        let r = self.radius;
+        let next: Vec<bool> = (n / 4 .. 3 * n / 4).map(|i| {
+            let code = cells[i - r ..= i + r].iter().fold(0, |k, c| k << 1 | *c as usize);
+            self.table[code]
+        }).collect();
+        self.build(&next)
+    }
+    // The centre half of `id` after 2^j generations.
+    fn result(&mut self, id: Id, j: u32) -> Id {
+        let level = self.nodes[id].level;
+        if self.nodes[id].pop == 0 {
+            return self.empty(level - 1);
+        }
+        if level == self.rho + 2 {
+            return self.base(id);
+        }
+        if let Some(res) = self.results.get(&(id, j)) {
+            return *res;
+        }
+        // Three overlapping halves, each brought to the halfway point (or
+        // just cut down to their centres if we're taking a smaller step than
+        // this level allows), then two overlapping halves of those.
+        let full = j == level - 2 - self.rho;
+        let (a, b) = self.nodes[id].children;
+        let mid = self.join(self.nodes[a].children.1, self.nodes[b].children.0);
+        let mut parts = [a, mid, b];
+        for p in parts.iter_mut() {
+            *p = if full { self.result(*p, j - 1) } else { self.centre(*p) };
+        }
+        let inner = if full { j - 1 } else { j };
+        let m0 = self.join(parts[0], parts[1]);
+        let m1 = self.join(parts[1], parts[2]);
+        let (r0, r1) = (self.result(m0, inner), self.result(m1, inner));
+        let res = self.join(r0, r1);
+        self.results.insert((id, j), res);
+        res
+    }
+
+    // Position of the first (or last) live cell within the node.
+    fn edge(&self, id: Id, last: bool) -> Option<u64> {
+        let mut node = &self.nodes[id];
+        if node.pop == 0 {
+            return None;
+        }
+        let mut pos = 0;
+        while node.level > 0 {
+            let (a, b) = node.children;
+            let take_b = if last { self.nodes[b].pop > 0 } else { self.nodes[a].pop == 0 };
+            if take_b {
+                pos += 1u64 << (node.level - 1);
+                node = &self.nodes[b];
+            } else {
+                node = &self.nodes[a];
+            }
+        }
+        Some(pos)
+    }
+    // Doubles the universe, keeping what's there in the middle.
+    fn expand(&mut self, u: Universe) -> Universe {
+        let level = self.nodes[u.root].level;
+        assert!(level < MAX_LEVEL, "universe too large: positions would overflow");
+        let e = self.empty(level - 1);
+        let (a, b) = self.nodes[u.root].children;
+        let (left, right) = (self.join(e, a), self.join(b, e));
+        Universe { root: self.join(left, right), origin: u.origin - (1i64 << (level - 1)) }
+    }
+
+    pub fn load(&mut self, line: &NumberLine<bool>) -> Universe {
+        let (pattern, offset) = line.canonical();
+        let mut cells = pattern.cells().to_vec();
+        cells.resize(cells.len().next_power_of_two().max(4 << self.rho), false);
+        Universe { root: self.build(&cells), origin: offset }
+    }
+    /// `u` after `gens` generations, done as one power-of-two jump per bit
+    /// of `gens`. Panics if `gens` is more than `MAX_GENS`.
+    pub fn advance(&mut self, u: &Universe, gens: u64) -> Universe {
+        assert!(gens <= MAX_GENS, "can't advance {gens} generations at once, the most is {MAX_GENS}");
+        let mut u = *u;
+        for j in (0..u64::BITS).filter(|j| gens >> j & 1 == 1) {
+            let reach = 1u64 << (j + self.rho);
+            loop {
+                let level = self.nodes[u.root].level;
+                let q = 1u64 << (level - 2);
+                let fits = level >= j + 2 + self.rho
+                    && self.edge(u.root, false).is_none_or(|first| first >= q + reach)
+                    && self.edge(u.root, true).is_none_or(|last| last + reach < 3 * q);
+                if fits {
+                    break;
+                }
+                u = self.expand(u);
+            }
+            let q = 1i64 << (self.nodes[u.root].level - 2);
+            u = Universe { root: self.result(u.root, j), origin: u.origin + q };
+        }
+        u
+    }
+    pub fn population(&self, u: &Universe) -> u64 {
+        self.nodes[u.root].pop
+    }
+    /// Sum of the indices of the live cells.
+    pub fn live_sum(&self, u: &Universe) -> i128 {
+        let node = &self.nodes[u.root];
+        u.origin as i128 * node.pop as i128 + node.sum as i128
+    }
+    pub fn live_cells(&self, u: &Universe) -> Vec<i64> {
+        let mut out = Vec::new();
+        self.collect_live(u.root, u.origin, &mut out);
+        out
+    }
+    fn collect_live(&self, id: Id, start: i64, out: &mut Vec<i64>) {
+        let node = &self.nodes[id];
+        if node.pop == 0 {
+            return;
+        }
+        if node.level == 0 {
+            out.push(start);
+            return;
+        }
+        let (a, b) = node.children;
+        self.collect_live(a, start, out);
+        self.collect_live(b, start + (1i64 << (node.level - 1)), out);
+    }
+    /// The universe as a `NumberLine`, which has to be able to hold
+    /// everything from the first live cell to the last.
+    pub fn to_line(&self, u: &Universe) -> NumberLine<bool> {
+        let live = self.live_cells(u);
+        let mut line = NumberLine::from_initial(&[], false);
+        if let (Some(first), Some(last)) = (live.first(), live.last()) {
+            line = NumberLine::new(*first, *last, false);
+            for idx in live {
+                line[idx] = true;
+            }
+        }
+        line
+    }
+    /// Like `advance`, from and to a `NumberLine`.
+    pub fn evolve(&mut self, line: &NumberLine<bool>, gens: u64) -> NumberLine<bool> {
+        let u = self.load(line);
+        let u = self.advance(&u, gens);
+        self.to_line(&u)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hashlife() {
+        let rule: Rule<bool> = "\
+...## => #\n..#.. => #\n.#... => #\n.#.#. => #\n.#.## => #\n.##.. => #\n.#### => #
+#.#.# => #\n#.### => #\n##.#. => #\n##.## => #\n###.. => #\n###.# => #\n####. => #\n"
+            .parse().unwrap();
+        let pots: Vec<bool> = "#..#.#..##......###...###".chars().map(|c| c == '#').collect();
+        let mut life = Hashlife::new(&rule);
+        let u = life.load(&NumberLine::from_initial(&pots, false));
+        let gen20 = life.advance(&u, 20);
+        assert_eq!(life.live_sum(&gen20), 325);
+        let far = life.advance(&u, 50_000_000_000);
+        assert_eq!(life.live_sum(&far), 999999999374);
+
+        // rule 90 never settles; check it against plain stepping, then far out
+        let rule: Rule<bool> = "#.# => .\n#.. => #\n..# => #\n##. => #\n.## => #\n### => .\n".parse().unwrap();
+        let mut life = Hashlife::new(&rule);
+        let mut line = NumberLine::from_initial(&[true, false, true, true], false);
+        for gen in 0..40 {
+            assert_eq!(life.evolve(&NumberLine::from_initial(&[true, false, true, true], false), gen).canonical(),
+                       line.canonical());
+            line = rule.step(&line);
+        }
+        let u = life.load(&NumberLine::from_initial(&[true], false));
+        let far = life.advance(&u, 1 << 40);
+        assert_eq!(life.population(&far), 2);
+        assert_eq!(life.live_cells(&far), vec![-(1 << 40), 1 << 40]);
+        assert_eq!(life.live_sum(&far), 0);
+
+        // as far as it goes, with every bit of the count set
+        let far = life.advance(&u, MAX_GENS - 1);
+        assert_eq!(life.population(&far), 1 << 48);
+        assert_eq!(life.live_sum(&far), 0);
+        let far = life.advance(&u, MAX_GENS);
+        assert_eq!(life.live_cells(&far), vec![-(1 << 48), 1 << 48]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the most is")]
+    fn too_far() {
+        let rule: Rule<bool> = "#.. => #\n..# => #\n".parse().unwrap();
+        let mut life = Hashlife::new(&rule);
+        let u = life.load(&NumberLine::from_initial(&[true], false));
+        life.advance(&u, MAX_GENS + 1);
+    }
+}
@@ -0,0 +1,210 @@
+//! Timing for the days in `advent2018::days`: repeated runs summarised as
+//! min/median/max, saved as a JSON baseline and compared against an earlier
+//! one.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::days::{Part, Solver};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty());
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        Self {
+            min: sorted[0],
+            median: sorted[sorted.len() / 2],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = |d: Duration| format!("{d:.1?}");
+        write!(f, "min {:>10}  median {:>10}  max {:>10}", d(self.min), d(self.median), d(self.max))
+    }
+}
+
+/// Stats for each phase of a day, by name (`parse`, `part1`, `part2`).
+pub type Timings = Vec<(&'static str, Stats)>;
+
+/// Runs `f` `reps` times.
+pub fn time<T, F: FnMut() -> T>(reps: usize, mut f: F) -> Stats {
+    let samples: Vec<Duration> = (0..reps.max(1)).map(|_| {
+        let start = Instant::now();
+        black_box(f());
+        start.elapsed()
+    }).collect();
+    Stats::from_samples(&samples)
+}
+
+/// Times a day's parse and each of `parts`. The parts all work from one
+/// parse.
+pub fn solver<S: Solver>(input: &str, parts: &[Part], reps: usize) -> Timings {
+    let mut out = vec![("parse", time(reps, || S::parse(input)))];
+    let parsed = S::parse(input);
+    for part in parts {
+        out.push(match part {
+            Part::One => ("part1", time(reps, || S::part1(&parsed))),
+            Part::Two => ("part2", time(reps, || S::part2(&parsed))),
+        });
+    }
+    out
+}
+
+/// Stats by name, e.g. `day15.part2`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    pub entries: BTreeMap<String, Stats>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Regressed,
+    Improved,
+    Unchanged,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    pub name: String,
+    pub old: Duration,
+    pub new: Duration,
+    /// Change in median, as a fraction of the old one.
+    pub change: f64,
+    pub verdict: Verdict,
+}
+
+impl Baseline {
+    pub fn insert(&mut self, name: String, stats: Stats) {
+        self.entries.insert(name, stats);
+    }
+
+    /// One entry per line, durations in nanoseconds.
+    pub fn to_json(&self) -> String {
+        let lines: Vec<String> = self.entries.iter().map(|(name, s)| format!(
+            "  \"{name}\": {{\"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+            s.min.as_nanos(), s.median.as_nanos(), s.max.as_nanos(),
+        )).collect();
+        format!("{{\n{}\n}}\n", lines.join(",\n"))
+    }
+
+    /// Reads what `to_json` writes. Anything in the object that isn't a
+    /// complete entry is an error rather than being left out.
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(
+                r#""([^"]+)":\s*\{\s*"min_ns":\s*(\d+),\s*"median_ns":\s*(\d+),\s*"max_ns":\s*(\d+)\s*\}"#
+            ).unwrap();
+        }
+        if !s.trim_start().starts_with('{') || !s.trim_end().ends_with('}') {
+            return Err("baseline is not a JSON object".into());
+        }
+        let ns = |m: Option<regex::Match>| -> Result<Duration, String> {
+            let v = m.unwrap().as_str();
+            v.parse().map(Duration::from_nanos).map_err(|e| format!("{v}: {e}"))
+        };
+        // between the entries there should only be the separating commas
+        let body = &s.trim()[1 .. s.trim().len() - 1];
+        let gap = |text: &str, comma: bool| -> Result<(), String> {
+            let rest = match text.trim().strip_prefix(',') {
+                Some(rest) if comma => rest.trim(),
+                _ if comma => return Err(format!("missing comma before: {}", text.trim())),
+                _ => text.trim(),
+            };
+            match rest.lines().next() {
+                None => Ok(()),
+                Some(line) => Err(format!("invalid baseline entry: {line}")),
+            }
+        };
+        let mut baseline = Baseline::default();
+        let mut end = 0;
+        for caps in RE.captures_iter(body) {
+            let whole = caps.get(0).unwrap();
+            gap(&body[end .. whole.start()], end > 0)?;
+            end = whole.end();
+            let stats = Stats { min: ns(caps.get(2))?, median: ns(caps.get(3))?, max: ns(caps.get(4))? };
+            baseline.insert(caps[1].to_string(), stats);
+        }
+        gap(&body[end ..], end > 0 && !body[end ..].trim().is_empty())?;
+        Ok(baseline)
+    }
+
+    /// The names that `other` has no entry for.
+    pub fn only_in<'a>(&'a self, other: &Baseline) -> Vec<&'a str> {
+        self.entries.keys().filter(|name| !other.entries.contains_key(*name)).map(String::as_str).collect()
+    }
+
+    /// Compares medians for names present in both; a change beyond
+    /// `threshold` (0.1 = 10%) either way counts.
+    pub fn compare(&self, newer: &Baseline, threshold: f64) -> Vec<Comparison> {
+        newer.entries.iter().filter_map(|(name, new)| {
+            let old = self.entries.get(name)?;
+            let (o, n) = (old.median.as_secs_f64(), new.median.as_secs_f64());
+            let change = if o > 0.0 { (n - o) / o } else { 0.0 };
+            let verdict = if change > threshold {
+                Verdict::Regressed
+            } else if change < -threshold {
+                Verdict::Improved
+            } else {
+                Verdict::Unchanged
+            };
+            Some(Comparison { name: name.clone(), old: old.median, new: new.median, change, verdict })
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn baseline() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(&[ms(5), ms(1), ms(9), ms(3), ms(4)]);
+        assert_eq!(stats, Stats { min: ms(1), median: ms(4), max: ms(9) });
+
+        let mut old = Baseline::default();
+        old.insert("day01.part1".into(), stats);
+        old.insert("day02.parse".into(), Stats { min: ms(10), median: ms(10), max: ms(10) });
+        old.insert("day03.part2".into(), Stats { min: ms(10), median: ms(10), max: ms(10) });
+        let json = old.to_json();
+        assert_eq!(Baseline::from_json(&json), Ok(old.clone()));
+        assert!(Baseline::from_json("nope").is_err());
+        assert_eq!(Baseline::from_json("{\n}\n"), Ok(Baseline::default()));
+        let typo = json.replacen("\"median_ns\": 10000000", "\"median\": 10000000", 1);
+        assert_eq!(Baseline::from_json(&typo),
+                   Err("invalid baseline entry: \"day02.parse\": {\"min_ns\": 10000000, \"median\": 10000000, \"max_ns\": 10000000},".into()));
+        assert!(Baseline::from_json(&json.replace("}\n}", "}\n  \"day09.part1\": {}\n}")).is_err());
+
+        let mut new = Baseline::default();
+        new.insert("day01.part1".into(), Stats { min: ms(1), median: ms(6), max: ms(9) });
+        new.insert("day02.parse".into(), Stats { min: ms(1), median: ms(5), max: ms(9) });
+        new.insert("day03.part2".into(), Stats { min: ms(1), median: ms(10), max: ms(90) });
+        new.insert("day04.part1".into(), stats);
+        let verdicts: Vec<_> = old.compare(&new, 0.1).iter().map(|c| (c.name.clone(), c.verdict)).collect();
+        assert_eq!(verdicts, vec![
+            ("day01.part1".into(), Verdict::Regressed),
+            ("day02.parse".into(), Verdict::Improved),
+            ("day03.part2".into(), Verdict::Unchanged),
+        ]);
+        assert_eq!(new.only_in(&old), vec!["day04.part1"]);
+        assert!(old.only_in(&new).is_empty());
+
+        let timings = solver::<crate::day01::Day01>("+1\n-2\n+3\n+1\n", &[Part::One, Part::Two], 3);
+        assert_eq!(timings.iter().map(|t| t.0).collect::<Vec<_>>(), vec!["parse", "part1", "part2"]);
+    }
+}
@@ -0,0 +1,285 @@
+use std::env;
+use std::fs;
+use std::io::{self, ErrorKind, Read};
+use std::process::ExitCode;
+use std::time::Instant;
+extern crate advent2018;
+use advent2018::answers::{input_hash, Check, Registry};
+use advent2018::bench::{Baseline, Verdict};
+use advent2018::days::{default_input, find, Day, Part, DAYS};
+
+const USAGE: &str = "\
+usage: advent2018 run <day> [--part 1|2] [--input PATH|-]
+       advent2018 run all [--part 1|2] [--slow]
+       advent2018 bench <day>|all [--part 1|2] [--input PATH|-] [--reps N]
+                        [--save PATH] [--baseline PATH] [--threshold PCT]
+                        [--slow]
+       advent2018 verify <day>|all [--part 1|2] [--input PATH|-]
+                         [--answers PATH] [--record] [--slow]
+       advent2018 list";
+
+// Runs any of the days from the table in advent2018::days. Input comes from
+// --input (`-` for stdin), or dayNN.input in the current directory.
+// `all` takes every day that has its dayNN.input, skipping the rest, and
+// leaves out the parts marked slow in the table unless given --slow. It
+// notes each day on stderr as it starts.
+//
+// `bench` times parsing and each part over --reps runs (default 5), can
+// --save the results as a JSON baseline, and with --baseline compares
+// medians against an earlier one, failing if any got slower by more than
+// --threshold percent (default 10). Names found on only one side are listed
+// but don't fail the run.
+//
+// `verify` runs the days and checks the answers against the ones recorded
+// for the same input in --answers (default answers.txt; see
+// advent2018::answers), failing on any mismatch. With --record, answers
+// that aren't in the file yet are added to it.
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("list") => {
+            for day in DAYS {
+                let parts: Vec<String> = day.parts.iter().map(|p| p.to_string()).collect();
+                let slow: Vec<String> = day.slow.iter().map(|p| p.to_string()).collect();
+                let slow = if slow.is_empty() { String::new() } else { format!("  slow {}", slow.join(",")) };
+                println!("day{:02}  parts {}{slow}", day.day, parts.join(","));
+            }
+            Ok(true)
+        },
+        Some("run") => Opts::parse(&args[1..]).and_then(|opts| run(&opts)).map(|_| true),
+        Some("bench") => Opts::parse(&args[1..]).and_then(|opts| bench(&opts)),
+        Some("verify") => Opts::parse(&args[1..]).and_then(|opts| verify(&opts)),
+        _ => Err(USAGE.into()),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+struct Opts {
+    which: String,
+    part: Option<Part>,
+    input: Option<String>,
+    reps: usize,
+    save: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+    answers: String,
+    record: bool,
+    slow: bool,
+}
+
+impl Opts {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut opts = Opts {
+            which: String::new(),
+            part: None,
+            input: None,
+            reps: 5,
+            save: None,
+            baseline: None,
+            threshold: 10.0,
+            answers: "answers.txt".into(),
+            record: false,
+            slow: false,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = |what: &str| args.next().cloned().ok_or(format!("{arg} needs {what}"));
+            match arg.as_str() {
+                "--part" => opts.part = Some(value("1 or 2")?.parse()?),
+                "--input" => opts.input = Some(value("a path")?),
+                "--reps" => opts.reps = value("a number")?.parse().map_err(|_| "--reps needs a number")?,
+                "--save" => opts.save = Some(value("a path")?),
+                "--baseline" => opts.baseline = Some(value("a path")?),
+                "--threshold" => opts.threshold = value("a percentage")?.parse().map_err(|_| "--threshold needs a number")?,
+                "--answers" => opts.answers = value("a path")?,
+                "--record" => opts.record = true,
+                "--slow" => opts.slow = true,
+                _ if opts.which.is_empty() => opts.which = arg.clone(),
+                _ => return Err(format!("unexpected argument: {arg}\n{USAGE}")),
+            }
+        }
+        if opts.which.is_empty() {
+            return Err(USAGE.into());
+        }
+        Ok(opts)
+    }
+
+    fn all(&self) -> bool { self.which == "all" }
+
+    fn parts(&self, day: &Day) -> Vec<Part> {
+        day.parts.iter().copied()
+            .filter(|p| self.part.is_none_or(|want| want == *p))
+            .filter(|p| self.slow || !self.all() || !day.slow.contains(p))
+            .collect()
+    }
+
+    // Under `all`, says which day is starting and which parts it's leaving
+    // out.
+    fn announce(&self, day: &Day) {
+        if !self.all() {
+            return;
+        }
+        let skipped: Vec<String> = day.slow.iter()
+            .filter(|p| !self.slow && self.part.is_none_or(|want| want == **p))
+            .map(|p| p.to_string()).collect();
+        if skipped.is_empty() {
+            eprintln!("day{:02}", day.day);
+        }
+        else {
+            eprintln!("day{:02}: skipping slow part {} (use --slow)", day.day, skipped.join(","));
+        }
+    }
+
+    // The days asked for, each with its input.
+    fn inputs(&self) -> Result<Vec<(&'static Day, String)>, String> {
+        if self.all() {
+            if self.input.is_some() {
+                return Err("--input can't be used with `all`".into());
+            }
+            return Ok(DAYS.iter().filter_map(|day| match fs::read_to_string(default_input(day.day)) {
+                Ok(text) => Some((day, text)),
+                Err(_) => {
+                    eprintln!("day{:02}: no {}, skipping", day.day, default_input(day.day));
+                    None
+                },
+            }).collect());
+        }
+        let day = self.which.trim_start_matches("day").parse().ok().and_then(find)
+            .ok_or_else(|| format!("no such day: {}", self.which))?;
+        if self.parts(day).is_empty() {
+            return Err(format!("day {} has no part {}", day.day, self.part.unwrap()));
+        }
+        let path = self.input.clone().unwrap_or_else(|| default_input(day.day));
+        let text = if path == "-" {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s).map_err(|e| e.to_string())?;
+            s
+        }
+        else {
+            fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?
+        };
+        Ok(vec![(day, text)])
+    }
+}
+
+fn run(opts: &Opts) -> Result<(), String> {
+    let all = opts.all();
+    for (day, text) in opts.inputs()? {
+        opts.announce(day);
+        let parts = opts.parts(day);
+        for (p, answer) in parts.iter().zip((day.run)(&text, &parts)) {
+            if all {
+                println!("Day {:02} part {p}: {answer}", day.day);
+            }
+            else {
+                println!("Part {p}: {answer}");
+            }
+        }
+    }
+    Ok(())
+}
+
+// Ok(false) if anything regressed against the baseline.
+fn bench(opts: &Opts) -> Result<bool, String> {
+    let old = match &opts.baseline {
+        Some(path) => Some(Baseline::from_json(&fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?)
+            .map_err(|e| format!("{path}: {e}"))?),
+        None => None,
+    };
+    let mut results = Baseline::default();
+    for (day, text) in opts.inputs()? {
+        opts.announce(day);
+        for (what, stats) in (day.bench)(&text, &opts.parts(day), opts.reps) {
+            let name = format!("day{:02}.{what}", day.day);
+            println!("{name:<12} {stats}");
+            results.insert(name, stats);
+        }
+    }
+    if let Some(path) = &opts.save {
+        fs::write(path, results.to_json()).map_err(|e| format!("{path}: {e}"))?;
+    }
+
+    let Some(old) = old else {
+        return Ok(true);
+    };
+    let comparisons = old.compare(&results, opts.threshold / 100.0);
+    for c in &comparisons {
+        let mark = match c.verdict {
+            Verdict::Regressed => "REGRESSED",
+            Verdict::Improved => "improved",
+            Verdict::Unchanged => "",
+        };
+        let line = format!("{:<12} {:>10} -> {:>10}  {:+6.1}%  {mark}",
+            c.name, format!("{:.1?}", c.old), format!("{:.1?}", c.new), c.change * 100.0);
+        println!("{}", line.trim_end());
+    }
+    for name in old.only_in(&results) {
+        println!("{name:<12} only in the baseline");
+    }
+    for name in results.only_in(&old) {
+        println!("{name:<12} not in the baseline");
+    }
+    let regressed = comparisons.iter().filter(|c| c.verdict == Verdict::Regressed).count();
+    if regressed > 0 {
+        println!("{regressed} regression(s) beyond {}%", opts.threshold);
+    }
+    Ok(regressed == 0)
+}
+
+// Ok(false) if any answer differs from the recorded one.
+fn verify(opts: &Opts) -> Result<bool, String> {
+    let path = &opts.answers;
+    let mut registry: Registry = match fs::read_to_string(path) {
+        Ok(text) => text.parse().map_err(|e| format!("{path}: {e}"))?,
+        Err(e) if e.kind() == ErrorKind::NotFound => Registry::default(),
+        Err(e) => return Err(format!("{path}: {e}")),
+    };
+    let (mut correct, mut wrong, mut unknown, mut recorded) = (0, 0, 0, 0);
+    let indent = |s: &str| s.trim_end().replace('\n', "\n              ");
+    for (day, text) in opts.inputs()? {
+        opts.announce(day);
+        let hash = input_hash(&text);
+        let parts = opts.parts(day);
+        let start = Instant::now();
+        let answers = (day.run)(&text, &parts);
+        // the parts are answered together, so the time goes on the first
+        let mut elapsed = format!("{:.1?}", start.elapsed());
+        for (&p, answer) in parts.iter().zip(answers) {
+            let elapsed = std::mem::take(&mut elapsed);
+            let name = format!("day{:02}.part{p}", day.day);
+            match registry.check(day.day, p, &hash, &answer) {
+                Check::Correct => {
+                    correct += 1;
+                    println!("ok     {name:<12} {elapsed:>10}");
+                },
+                Check::Wrong { expected } => {
+                    wrong += 1;
+                    println!("WRONG  {name:<12} {elapsed:>10}");
+                    println!("  expected    {}", indent(&expected));
+                    println!("  got         {}", indent(&answer));
+                },
+                Check::Unknown if opts.record => {
+                    recorded += 1;
+                    println!("new    {name:<12} {elapsed:>10}  recorded {}", indent(&answer));
+                    registry.insert(day.day, p, &hash, &answer);
+                },
+                Check::Unknown => {
+                    unknown += 1;
+                    println!("?      {name:<12} {elapsed:>10}  {}", indent(&answer));
+                },
+            }
+        }
+    }
+    if recorded > 0 {
+        fs::write(path, registry.to_string()).map_err(|e| format!("{path}: {e}"))?;
+    }
+    println!("{correct} ok, {wrong} wrong, {unknown} not recorded, {recorded} newly recorded");
+    Ok(wrong == 0)
+}
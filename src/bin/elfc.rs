@@ -0,0 +1,59 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::process::ExitCode;
+extern crate advent2018;
+use advent2018::vm::{compile::compile, validate, RunResult, VM};
+
+// Compiles the tiny language in advent2018::vm::compile to elfcode and
+// prints the program.
+//   elfc [--ip N] [--run] [FILE]
+// Reads stdin if no file is given. --run also executes the program and
+// prints the final registers instead.
+fn main() -> ExitCode {
+    let mut ip_reg = 5;
+    let mut run = false;
+    let mut path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ip" => ip_reg = args.next().and_then(|v| v.parse().ok()).expect("--ip needs a register number"),
+            "--run" => run = true,
+            _ => path = Some(arg),
+        }
+    }
+    let src = match path {
+        Some(p) => fs::read_to_string(&p).unwrap_or_else(|e| panic!("{p}: {e}")),
+        None => {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s).unwrap();
+            s
+        },
+    };
+
+    let prog = match compile(&src, ip_reg) {
+        Ok(prog) => prog,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        },
+    };
+    for d in validate(&prog) {
+        eprintln!("{d}");
+    }
+    if run {
+        let mut vm = VM::new();
+        vm.load(&prog);
+        match vm.run() {
+            RunResult::Halt => println!("{:?}", vm.r),
+            RunResult::Err(e) => panic!("Error while running program: {}", e),
+            _ => panic!(),
+        }
+    }
+    else {
+        for item in &prog {
+            println!("{item}");
+        }
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,34 @@
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+extern crate advent2018;
+use advent2018::vm::equiv::{check, Options, Verdict};
+use advent2018::vm::spec::parse;
+use advent2018::vm::ProgramItem;
+
+// Checks whether two elfcode programs compute the same final registers.
+//   elfequiv [--inputs 0,1] [--compare 0] [--fuel N] A B
+// Exits with failure if a counterexample is found.
+fn main() -> ExitCode {
+    let mut opts = Options::default();
+    let mut paths = Vec::new();
+    let mut args = env::args().skip(1);
+    let regs = |v: Option<String>| v.map(|v| v.split(',').map(|r| r.parse().expect("bad register")).collect());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--inputs" => opts.inputs = regs(args.next()),
+            "--compare" => opts.compare = regs(args.next()),
+            "--fuel" => opts.fuel = args.next().and_then(|v| v.parse().ok()).expect("--fuel needs a number"),
+            _ => paths.push(arg),
+        }
+    }
+    assert_eq!(paths.len(), 2, "need two program files");
+    let progs: Vec<Vec<ProgramItem>> = paths.iter().map(|p| {
+        let src = fs::read_to_string(p).unwrap_or_else(|e| panic!("{p}: {e}"));
+        parse(&src).unwrap_or_else(|line| panic!("{p}: line {line}: invalid input"))
+    }).collect();
+
+    let verdict = check(&progs[0], &progs[1], &opts);
+    println!("{verdict}");
+    if matches!(verdict, Verdict::Different(_)) { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -0,0 +1,42 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+extern crate advent2018;
+use advent2018::vm::spec::{discover, run_file};
+
+// Runs elfcode spec files (see advent2018::vm::Meta for the directives) and
+// reports which ones pass. Arguments are files or directories to search for
+// .elf files; with none, the elf/ directory is used.
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        args.push("elf".into());
+    }
+    let mut files: Vec<PathBuf> = Vec::new();
+    for arg in args {
+        let path = Path::new(&arg);
+        if path.is_dir() {
+            files.extend(discover(path).unwrap_or_else(|e| panic!("{arg}: {e}")));
+        }
+        else {
+            files.push(path.to_owned());
+        }
+    }
+
+    let mut failed = 0;
+    for path in &files {
+        let result = run_file(path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        if result.passed() {
+            println!("PASS {} ({} steps)", path.display(), result.steps);
+        }
+        else {
+            failed += 1;
+            println!("FAIL {} ({} steps, registers {:?})", path.display(), result.steps, result.regs);
+            for f in &result.failures {
+                println!("  {}", f.to_string().replace('\n', "\n  "));
+            }
+        }
+    }
+    println!("{} passed, {failed} failed", files.len() - failed);
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
@@ -0,0 +1,47 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+extern crate advent2018;
+use advent2018::vm::Instruction;
+use advent2018::vm::spec::parse;
+use advent2018::vm::superopt::{scan, Options};
+
+// Looks for shorter equivalents of straight-line runs in an elfcode program.
+//   superopt [--window N] [--max-len N] [FILE]
+// Reads stdin if no file is given.
+fn main() {
+    let mut window = 3;
+    let mut opts = Options::default();
+    let mut path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--window" => window = args.next().and_then(|v| v.parse().ok()).expect("--window needs a number"),
+            "--max-len" => opts.max_len = args.next().and_then(|v| v.parse().ok()).expect("--max-len needs a number"),
+            _ => path = Some(arg),
+        }
+    }
+    let src = match path {
+        Some(p) => fs::read_to_string(&p).unwrap_or_else(|e| panic!("{p}: {e}")),
+        None => {
+            let mut s = String::new();
+            io::stdin().read_to_string(&mut s).unwrap();
+            s
+        },
+    };
+    let prog = parse(&src).unwrap_or_else(|line| panic!("line {line}: invalid input"));
+
+    let found = scan(&prog, window, &opts);
+    for rep in &found {
+        println!("{:>4}: {}  =>  {}", rep.addr, show(&rep.original), show(&rep.replacement));
+    }
+    println!("{} replacement(s) found", found.len());
+}
+
+fn show(insts: &[Instruction]) -> String {
+    if insts.is_empty() {
+        "(nothing)".to_owned()
+    } else {
+        insts.iter().map(|i| i.to_string()).collect::<Vec<_>>().join("; ")
+    }
+}
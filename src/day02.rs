@@ -0,0 +1,68 @@
+use std::vec::Vec;
+use counter::Counter;
+use itertools::Itertools;
+use crate::days::{lines, Solver};
+
+fn part1(input: &[String]) -> i32 {
+    let (twos, threes) = input.iter()
+        .map(|line| line.chars().collect::<Counter<_>>())
+        .map(|counter| counter.values().fold((0,0), |(two,thr), v| match v {
+            2 => ((two+1).min(1), thr),
+            3 => (two, (thr+1).min(1)),
+            _ => (two, thr),
+        }))
+        .fold((0,0), |a,b| (a.0+b.0, a.1+b.1));
+    twos * threes
+}
+
+fn part2(input: &[String]) -> String {
+    input.iter()
+        .tuple_combinations()
+        .filter_map(|(a, b)| check_string_diff(a, b))
+        .next()
+        .unwrap()
+}
+
+fn check_string_diff(s1: &str, s2: &str) -> Option<String> {
+    let common: String = s1.chars()
+        .zip(s2.chars())
+        .filter_map(|(c1, c2)| if c1 == c2 { Some(c1) } else { None })
+        .collect();
+    if common.len() == s1.len() - 1 {
+        Some(common)
+    }
+    else {
+        None
+    }
+}
+
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input = Vec<String>;
+    type Part1 = i32;
+    type Part2 = String;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day02_test() {
+        let input: Vec<String> = test_input(include_str!("day02.testinput"));
+        assert_eq!(part1(&input), 12);
+        let input: Vec<String> = test_input(include_str!("day02.testinput2"));
+        assert_eq!(part2(&input), String::from("fgij"));
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::intervals::RectSet;
+use crate::days::{lines, Solver};
+
+#[derive(Debug)]
+pub struct Claim {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub h: i32,
+    pub w: i32,
+}
+
+impl Claim {
+    pub fn intersect(&self, other: &Claim) -> Option<Claim> {
+        if other.x + other.w <= self.x || other.x >= self.x + self.w
+            || other.y + other.h <= self.y || other.y >= self.y + self.h
+        {
+            None
+        }
+        else {
+            let nx = self.x.max(other.x);
+            let ny = self.y.max(other.y);
+            let nx2 = (self.x + self.w).min(other.x + other.w);
+            let ny2 = (self.y + self.h).min(other.y + other.h);
+            Some(Claim {
+                name: format!("{} {}", self.name, other.name),
+                x: nx,
+                y: ny,
+                w: nx2 - nx,
+                h: ny2 - ny,
+            })
+        }
+    }
+
+    //fn area(&self) -> i32 {
+    //    self.w * self.h
+    //}
+}
+
+impl FromStr for Claim {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"^.(.*?) @ (-?\d+),(-?\d+): (-?\d+)x(-?\d+)").unwrap();
+        }
+        match RE.captures(s) {
+            None => Err(format!("invalid input: {}", s)),
+            Some(caps) => {
+                let name:String = caps.get(1).unwrap().as_str().to_string();
+                let x:i32 = caps.get(2).unwrap().as_str().parse::<i32>().unwrap();
+                let y:i32 = caps.get(3).unwrap().as_str().parse::<i32>().unwrap();
+                let w:i32 = caps.get(4).unwrap().as_str().parse::<i32>().unwrap();
+                let h:i32 = caps.get(5).unwrap().as_str().parse::<i32>().unwrap();
+                Ok(Claim {name, x, y, h, w})
+            },
+        }
+    }
+}
+
+fn bothparts(data: &[Claim]) -> (usize, String) {
+    let mut overlaps = RectSet::new();
+    let mut allkeys: HashSet<String> = HashSet::from_iter(
+        data.iter().map(|x| x.name.to_string())
+    );
+    data.iter()
+        .tuple_combinations()
+        .for_each(|(a, b)| {
+            if let Some(o) = a.intersect(b) {
+                allkeys.remove(&a.name);
+                allkeys.remove(&b.name);
+                overlaps.insert(o.x as i64 .. (o.x + o.w) as i64, o.y as i64 .. (o.y + o.h) as i64);
+            }
+        });
+    (overlaps.area() as usize, allkeys.into_iter().next().unwrap())
+}
+
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input = Vec<Claim>;
+    type Part1 = usize;
+    type Part2 = String;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        bothparts(input).0
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        bothparts(input).1
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        bothparts(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day03_test() {
+        let input: Vec<Claim> = test_input(include_str!("day03.testinput"));
+        let (part1, part2) = bothparts(&input);
+        assert_eq!(part1, 4);
+        assert_eq!(part2, String::from("3"));
+    }
+}
@@ -0,0 +1,66 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::days::{lines, Solver};
+
+lazy_static! {
+    static ref RE: Regex = Regex::new(r"Aa|aA|Bb|bB|Cc|cC|Dd|dD|Ee|eE|Ff|fF|Gg|gG|Hh|hH|Ii|iI|Jj|jJ|Kk|kK|Ll|lL|Mm|mM|Nn|nN|Oo|oO|Pp|pP|Qq|qQ|Rr|rR|Ss|sS|Tt|tT|Uu|uU|Vv|vV|Ww|wW|Xx|xX|Yy|yY|Zz|zZ").unwrap();
+}
+
+pub fn react(input: &str) -> String {
+    let mut polymer = input.to_owned();
+    loop {
+        let np = RE.replace_all(&polymer, "").to_string();
+        if np == polymer { break; }
+        polymer = np;
+    }
+    polymer
+}
+
+fn part1(input: &str) -> usize {
+    let polymer = react(input);
+    polymer.len()
+}
+
+fn part2(input: &str) -> usize {
+    let mut minlen = input.len();
+    for c in b'a'..=b'z' {
+        let mut pat = String::from("(?i)");
+        pat.push(c as char);
+        let reg = Regex::new(&pat).unwrap();
+        let size = react(reg.replace_all(input, "").as_ref()).len();
+        if size < minlen {
+            minlen = size;
+        }
+    }
+    minlen
+}
+
+pub struct Day05;
+
+impl Solver for Day05 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+    fn parse(input: &str) -> Self::Input {
+        lines::<String>(input).swap_remove(0)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day05_test() {
+        let input: Vec<String> = test_input("dabAcCaCBAcCcaDA");
+        assert_eq!(part1(&input[0]), 10);
+        assert_eq!(part2(&input[0]), 4);
+    }
+}
@@ -0,0 +1,91 @@
+use ya_advent_lib::coords::Coord2D;
+use crate::days::{lines, Solver};
+use crate::number_plane::NumberPlane;
+
+fn bothparts(data: &[Coord2D], limit: i64) -> (i64, i64) {
+    let mut points = NumberPlane::new(false);
+    for c in data.iter() {
+        points.set(c.x, c.y, true);
+    }
+    let Some((xs, ys)) = points.bounding_box() else {
+        return (0, 0);
+    };
+    // a cell d steps outside the box is at least d from every point, so the
+    // region can't reach further than this
+    let margin = limit / data.len() as i64;
+    let mut grid = NumberPlane::new(-1);
+    let mut region = 0;
+    for x in xs.start - margin .. xs.end + margin {
+        for y in ys.start - margin .. ys.end + margin {
+            let mut mindex = -1;
+            let mut mindist = -1;
+            let mut totaldist = 0;
+            for (idx, point) in data.iter().enumerate() {
+                let dist = (point.x - x).abs() + (point.y - y).abs();
+                totaldist += dist;
+                if mindist == -1 || mindist > dist {
+                    mindist = dist;
+                    mindex = idx as i64;
+                }
+                else if mindist == dist {
+                    mindex = -1;
+                }
+            }
+            if xs.contains(&x) && ys.contains(&y) {
+                grid.set(x, y, mindex);
+            }
+            if totaldist < limit {
+                region += 1;
+            }
+        }
+    }
+
+    // anything reaching the edge of the box goes on forever
+    let mut counts = vec![0i64; data.len()];
+    let edges = grid.row(ys.start).chain(grid.row(ys.end - 1))
+        .chain(grid.col(xs.start)).chain(grid.col(xs.end - 1));
+    for (_, val) in edges {
+        if val >= 0 {
+            counts[val as usize] = -1;
+        }
+    }
+    for (_, _, val) in grid.iter() {
+        if val >= 0 && counts[val as usize] != -1 {
+            counts[val as usize] += 1;
+        }
+    }
+    let maxarea = counts.iter().copied().max().unwrap_or(0).max(0);
+    (maxarea, region)
+}
+
+pub struct Day06;
+
+impl Solver for Day06 {
+    type Input = Vec<Coord2D>;
+    type Part1 = i64;
+    type Part2 = i64;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        bothparts(input, 10000).0
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        bothparts(input, 10000).1
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        bothparts(input, 10000)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day06_test() {
+        let input: Vec<Coord2D> = test_input(include_str!("day06.testinput"));
+        assert_eq!(bothparts(&input, 32), (17, 16));
+    }
+}
@@ -0,0 +1,114 @@
+use std::str::FromStr;
+use std::vec::Vec;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::days::{lines, Solver};
+use crate::number_plane::NumberPlane;
+
+#[derive(Clone, Copy)]
+pub struct Point {
+    pub x_loc: i64,
+    pub y_loc: i64,
+    pub x_vel: i64,
+    pub y_vel: i64,
+}
+
+impl FromStr for Point {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(
+                r"position=.\s*(-?\d+),\s*(-?\d+). velocity=.\s*(-?\d+),\s*(-?\d+)"
+            ).unwrap();
+        }
+        match RE.captures(s) {
+            None => Err(format!("invalid input: {}", s)),
+            Some(caps) => {
+                let x_loc = caps.get(1).unwrap().as_str().parse::<i64>().unwrap();
+                let y_loc = caps.get(2).unwrap().as_str().parse::<i64>().unwrap();
+                let x_vel = caps.get(3).unwrap().as_str().parse::<i64>().unwrap();
+                let y_vel = caps.get(4).unwrap().as_str().parse::<i64>().unwrap();
+                Ok(Point {
+                    x_loc, y_loc, x_vel, y_vel,
+                })
+            },
+        }
+    }
+}
+
+fn area(stars: &[Point]) -> i64 {
+    let xs = stars.iter().map(|s| s.x_loc);
+    let ys = stars.iter().map(|s| s.y_loc);
+    (xs.clone().max().unwrap() - xs.min().unwrap() + 1) * (ys.clone().max().unwrap() - ys.min().unwrap() + 1)
+}
+
+fn bothparts(data: &[Point]) -> (String, i64) {
+    let mut stars: Vec<Point> = data.to_vec();
+    let mut area_now = area(&stars);
+    let mut elapsed:i64 = 0;
+    loop {
+        for s in stars.iter_mut() {
+            s.x_loc += s.x_vel;
+            s.y_loc += s.y_vel;
+        }
+        let newarea = area(&stars);
+        if newarea > area_now {
+            for s in stars.iter_mut() {
+                s.x_loc -= s.x_vel;
+                s.y_loc -= s.y_vel;
+            }
+            break;
+        }
+        area_now = newarea;
+        elapsed += 1;
+    }
+    let mut sky = NumberPlane::new(false);
+    for s in stars.iter() {
+        sky.set(s.x_loc, s.y_loc, true);
+    }
+    (sky.format(|c| if c {'#'} else {'.'}), elapsed)
+}
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    type Input = Vec<Point>;
+    type Part1 = String;
+    type Part2 = i64;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        bothparts(input).0
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        bothparts(input).1
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        bothparts(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day10_test() {
+        let input: Vec<Point> = test_input(include_str!("day10.testinput"));
+        let (part1, part2) = bothparts(&input);
+        assert_eq!(part1, String::from(
+"\
+#...#..###
+#...#...#.
+#...#...#.
+#####...#.
+#...#...#.
+#...#...#.
+#...#...#.
+#...#..###
+"));
+        assert_eq!(part2, 3);
+    }
+}
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+use std::vec::Vec;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::automaton::{Rule, Unlisted};
+use crate::number_line::NumberLine;
+use crate::days::{lines, Solver};
+
+enum InputItem {
+    InitialState(NumberLine<bool>),
+    MapItem(Vec<bool>, bool),
+    None,
+}
+
+impl FromStr for InputItem {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE_INIT: Regex = Regex::new(
+                r"initial state: ([#.]+)",
+            ).unwrap();
+        }
+        lazy_static! {
+            static ref RE_BLANK: Regex = Regex::new(
+                r"^\s*$",
+            ).unwrap();
+        }
+        lazy_static! {
+            static ref RE_MAP: Regex = Regex::new(
+                r"([#.]+) => ([#.])",
+            ).unwrap();
+        }
+        if let Some(caps) = RE_INIT.captures(s) {
+            let isstr = caps.get(1).unwrap().as_str();
+            return NumberLine::parse_with(isstr, false, |c| Some(c == '#'))
+                .map(InputItem::InitialState);
+        }
+        if RE_BLANK.captures(s).is_some() {
+            return Ok(InputItem::None);
+        }
+        if let Some(caps) = RE_MAP.captures(s) {
+            let sstr = caps.get(1).unwrap().as_str();
+            let ostr = caps.get(2).unwrap().as_str();
+            return Ok(
+                InputItem::MapItem(sstr.chars().map(|c| c == '#').collect(), ostr.starts_with('#'))
+            );
+        }
+        Err("invalid input line".to_string())
+    }
+}
+
+fn sum_pots(pots: &NumberLine<bool>) -> i64 {
+    pots.enumerate().filter(|(_, val)| *val).map(|(idx, _)| idx).sum()
+}
+
+fn part1(rule: &Rule<bool>, initial: &NumberLine<bool>) -> i64 {
+    sum_pots(&rule.nth(initial, 20))
+}
+
+fn part2(rule: &Rule<bool>, initial: &NumberLine<bool>) -> i64 {
+    sum_pots(&rule.nth(initial, 50_000_000_000))
+}
+
+fn setup(input: &[InputItem]) -> (Rule<bool>, NumberLine<bool>) {
+    let mut rule = Rule::new(2, Unlisted::Value(false));
+    let mut initial: Option<NumberLine<bool>> = None;
+    for d in input.iter() {
+        match d {
+            InputItem::InitialState(v) => {initial = Some(v.clone());},
+            InputItem::MapItem(k, v) => {rule.insert(k, *v);},
+            InputItem::None => (),
+        }
+    }
+    let initial = initial.unwrap();
+    (rule, initial)
+}
+
+pub struct Day12;
+
+impl Solver for Day12 {
+    type Input = (Rule<bool>, NumberLine<bool>);
+    type Part1 = i64;
+    type Part2 = i64;
+    fn parse(input: &str) -> Self::Input {
+        setup(&lines::<InputItem>(input))
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(&input.0, &input.1)
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(&input.0, &input.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day12_test() {
+        let input: Vec<InputItem> = test_input(include_str!("day12.testinput"));
+        let (rule, initial) = setup(&input);
+        assert_eq!(part1(&rule, &initial), 325);
+        assert_eq!(part2(&rule, &initial), 999999999374);
+    }
+}
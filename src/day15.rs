@@ -0,0 +1,365 @@
+use std::vec::Vec;
+use ya_advent_lib::grid::Grid;
+use crate::days::{lines, Solver};
+use crate::search::Search;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MapCell {
+    Empty,
+    Wall,
+    Elf(usize),
+    Goblin(usize),
+}
+impl MapCell {
+    fn is_empty(&self) -> bool {
+        matches!(*self, MapCell::Empty)
+    }
+    fn from_unit(unit: &Unit) -> Self {
+        if unit.is_elf {
+            MapCell::Elf(unit.id)
+        } else {
+            MapCell::Goblin(unit.id)
+        }
+    }
+}
+
+pub struct Unit {
+    pub id: usize,
+    pub is_elf: bool,
+    pub x: i64,
+    pub y: i64,
+    pub hp: i64,
+    pub attack: i64,
+}
+impl Unit {
+    fn new(id: usize, x: i64, y: i64, is_elf: bool, attack: i64) -> Self {
+        Self {
+            id,
+            is_elf,
+            x,
+            y,
+            hp: 200,
+            attack,
+        }
+    }
+    pub fn is_alive(&self) -> bool {
+        self.hp > 0
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Action {
+    None,
+    Move(i64, i64),
+    Attack(usize),
+    Finished,
+}
+
+pub struct Battle {
+    grid: Grid<MapCell>,
+    units: Vec<Unit>,
+    elf_attack: i64,
+}
+impl Battle {
+    pub fn new(input: &[String], elf_attack: i64) -> Self {
+        let width = input.iter().map(|s| s.len()).max().unwrap() as i64;
+        let height = input.len() as i64;
+        let mut inst = Self {
+            grid: Grid::new(0, 0, width-1, height-1, MapCell::Empty),
+            units: Vec::new(),
+            elf_attack,
+        };
+        for (uy, line) in input.iter().enumerate() {
+            let y = uy as i64;
+            for (ux, c) in line.chars().enumerate() {
+                let x = ux as i64;
+                match c {
+                    '#' => inst.put_wall(x, y),
+                    'E' => inst.put_elf(x, y),
+                    'G' => inst.put_goblin(x, y),
+                    _ => (),
+                };
+            }
+        }
+        inst
+    }
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+    pub fn grid(&self) -> &Grid<MapCell> {
+        &self.grid
+    }
+    fn put_wall(&mut self, x: i64, y: i64) {
+        self.grid.set(x, y, MapCell::Wall);
+    }
+    fn put_elf(&mut self, x: i64, y: i64) {
+        self.grid.set(x, y, MapCell::Elf(self.units.len()));
+        self.units.push(Unit::new(self.units.len(), x, y, true, self.elf_attack));
+    }
+    fn put_goblin(&mut self, x: i64, y: i64) {
+        self.grid.set(x, y, MapCell::Goblin(self.units.len()));
+        self.units.push(Unit::new(self.units.len(), x, y, false, 3));
+    }
+
+    pub fn step(&mut self) -> bool {
+        let mut unit_ids: Vec<usize> =
+            self.units.iter().filter(|u| u.is_alive()).map(|u| u.id).collect();
+        unit_ids.sort_unstable_by(|ia, ib| {
+            let a = &self.units[*ia];
+            let b = &self.units[*ib];
+            a.y.cmp(&b.y).then_with(|| a.x.cmp(&b.x))
+        });
+        for id in unit_ids.iter() {
+            { // limit the scope of the immutable `unit` borrow
+                let unit = &self.units[*id];
+                if !unit.is_alive() { continue; }
+                assert_eq!(MapCell::from_unit(unit), self.grid.get(unit.x, unit.y));
+            }
+
+            match self.determine_action(*id) {
+                Action::Finished => return false,
+                Action::Move(x, y) => {
+                    self.move_to(*id, x, y);
+                    if let Action::Attack(enemy_id) = self.determine_attack(*id) {
+                        self.attack(*id, enemy_id);
+                    }
+                },
+                Action::Attack(enemy_id) => self.attack(*id, enemy_id),
+                Action::None => (),
+            }
+        }
+        true
+    }
+
+    fn determine_action(&self, unit_id: usize) -> Action {
+        if let Action::Attack(enemy_id) = self.determine_attack(unit_id) {
+            return Action::Attack(enemy_id);
+        }
+        let unit = &self.units[unit_id];
+
+        // Find the nearest squares in range of an enemy and take the first
+        // in reading order. Then search back from there: the unit's
+        // predecessor is its neighbour that comes first in reading order
+        // among those on a shortest path.
+        let here = (unit.x, unit.y);
+        let adjacent = |&(x, y): &(i64, i64)| [(x, y - 1), (x - 1, y), (x + 1, y), (x, y + 1)];
+        let steps = |p: &(i64, i64)| adjacent(p).into_iter()
+            .filter(|&(x, y)| (x, y) == here || self.grid.get(x, y).is_empty());
+        let in_range = |p: &(i64, i64)| adjacent(p).iter().any(|&(x, y)| self.is_enemy(unit, x, y).is_some());
+        let reading = |&(x, y): &(i64, i64)| (y, x);
+
+        let nearest = Search::bfs(steps).tiebreak(reading).to(here, in_range);
+        if let Some(&target) = nearest.goals().first() {
+            let back = Search::bfs(steps).tiebreak(reading).to(target, |&p| p == here);
+            let &(x, y) = back.prev(&here).unwrap();
+            return Action::Move(x, y);
+        }
+        if self.units.iter().filter(|u| u.is_elf != unit.is_elf).all(|u| !u.is_alive()) {
+            return Action::Finished;
+        }
+        Action::None
+    }
+
+    // see if there's an adjacent unit we can attack
+    fn determine_attack(&self, unit_id: usize) -> Action {
+        let unit = &self.units[unit_id];
+
+        let mut in_range: Vec<&Unit> = Vec::with_capacity(4);
+        if let Some(enemy) = self.is_enemy(unit, unit.x, unit.y - 1) {
+            in_range.push(enemy);
+        }
+        if let Some(enemy) = self.is_enemy(unit, unit.x - 1, unit.y) {
+            in_range.push(enemy);
+        }
+        if let Some(enemy) = self.is_enemy(unit, unit.x + 1, unit.y) {
+            in_range.push(enemy);
+        }
+        if let Some(enemy) = self.is_enemy(unit, unit.x, unit.y + 1) {
+            in_range.push(enemy);
+        }
+        if !in_range.is_empty() {
+            in_range.sort_by_key(|a| a.hp);
+            Action::Attack(in_range[0].id)
+        }
+        else {
+            Action::None
+        }
+    }
+
+    fn is_enemy(&self, unit: &Unit, x: i64, y:i64) -> Option<&Unit> {
+        if unit.is_elf {
+            match self.grid.get(x, y) {
+                MapCell::Goblin(id) => Some(&self.units[id]),
+                _ => None,
+            }
+        } else {
+            match self.grid.get(x, y) {
+                MapCell::Elf(id) => Some(&self.units[id]),
+                _ => None,
+            }
+        }
+    }
+
+    fn move_to(&mut self, unit_id: usize, x: i64, y: i64) {
+        let unit = &mut self.units[unit_id];
+        assert_eq!(self.grid.get(x, y), MapCell::Empty);
+        assert_eq!(MapCell::from_unit(unit), self.grid.get(unit.x, unit.y));
+        self.grid.set(unit.x, unit.y, MapCell::Empty);
+        unit.x = x;
+        unit.y = y;
+        self.grid.set(unit.x, unit.y, MapCell::from_unit(unit));
+    }
+
+    fn attack(&mut self, unit_id: usize, enemy_id: usize) {
+        assert_ne!(unit_id, enemy_id);
+        let split_at = unit_id.max(enemy_id);
+        let (left, right) = self.units.split_at_mut(split_at);
+        let unit: &mut Unit;
+        let enemy: &mut Unit;
+        if unit_id < enemy_id {
+            unit = &mut left[unit_id];
+            enemy = &mut right[0];
+        } else {
+            enemy = &mut left[enemy_id];
+            unit = &mut right[0];
+        }
+        enemy.hp -= unit.attack;
+        if enemy.hp <= 0 {
+            self.grid.set(enemy.x, enemy.y, MapCell::Empty);
+        }
+    }
+}
+
+fn part1(data: &[String]) -> i64 {
+    let mut battle = Battle::new(data, 3);
+    let mut turns = 0;
+
+    while battle.step() {
+        turns += 1;
+    }
+    turns * battle.units.iter().filter(|u| u.is_alive()).map(|u| u.hp).sum::<i64>()
+}
+
+fn part2(data: &[String]) -> i64 {
+    let mut lower = 3;
+    let mut upper = 200;
+    let mut lastwin = 0;
+    while lower + 1 < upper {
+        let mid = lower + (upper - lower) / 2;
+        //println!("testing {}", mid);
+        let mut battle = Battle::new(data, mid);
+        let mut turns = 0;
+        while battle.step() {
+            turns += 1;
+        }
+        let failed = battle.units.iter().any(|u| u.is_elf && !u.is_alive());
+        if failed {
+            lower = mid;
+        } else {
+            upper = mid;
+            let sum = battle.units.iter().filter(|u| u.is_alive()).fold(0, |sum, u| sum + u.hp);
+            lastwin = turns * sum;
+        }
+    }
+    lastwin
+}
+
+pub struct Day15;
+
+impl Solver for Day15 {
+    type Input = Vec<String>;
+    type Part1 = i64;
+    type Part2 = i64;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day15_test() {
+        let input = test_input::<String>(
+"#######
+#.G...#
+#...EG#
+#.#.#G#
+#..G#E#
+#.....#
+#######
+");
+        assert_eq!(part1(&input), 27730);
+        assert_eq!(part2(&input), 4988);
+
+        let input = test_input::<String>(
+"#######
+#G..#E#
+#E#E.E#
+#G.##.#
+#...#E#
+#...E.#
+#######
+");
+        assert_eq!(part1(&input), 36334);
+        assert_eq!(part2(&input), 29064);
+
+        let input = test_input::<String>(
+"#######
+#E..EG#
+#.#G.E#
+#E.##E#
+#G..#.#
+#..E#.#
+#######
+");
+        assert_eq!(part1(&input), 39514);
+        assert_eq!(part2(&input), 31284);
+
+        let input = test_input::<String>(
+"#######
+#E.G#.#
+#.#G..#
+#G.#.G#
+#G..#.#
+#...E.#
+#######
+");
+        assert_eq!(part1(&input), 27755);
+        assert_eq!(part2(&input), 3478);
+
+        let input = test_input::<String>(
+"#######
+#.E...#
+#.#..G#
+#.###.#
+#E#G#G#
+#...#G#
+#######
+");
+        assert_eq!(part1(&input), 28944);
+        assert_eq!(part2(&input), 6474);
+
+        let input = test_input::<String>(
+"#########
+#G......#
+#.E.#...#
+#..##..G#
+#...##..#
+#...#...#
+#.G...G.#
+#.....G.#
+#########
+");
+        assert_eq!(part1(&input), 18740);
+        assert_eq!(part2(&input), 1140);
+    }
+}
@@ -0,0 +1,232 @@
+use std::cell::RefCell;
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::number_plane::NumberPlane;
+use crate::days::{lines, Solver};
+
+pub enum InputItem {
+    Row(RangeInclusive<i64>, i64),
+    Col(i64, RangeInclusive<i64>),
+}
+
+impl FromStr for InputItem {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref RE_ROW: Regex = Regex::new(
+                r"y=(\d+), x=(\d+)\.\.(\d+)",
+            ).unwrap();
+        }
+        lazy_static! {
+            static ref RE_COL: Regex = Regex::new(
+                r"x=(\d+), y=(\d+)\.\.(\d+)",
+            ).unwrap();
+        }
+        if let Some(caps) = RE_ROW.captures(s) {
+            let y = caps.get(1).unwrap().as_str().parse::<i64>().unwrap();
+            let x1 = caps.get(2).unwrap().as_str().parse::<i64>().unwrap();
+            let x2 = caps.get(3).unwrap().as_str().parse::<i64>().unwrap();
+            return Ok(InputItem::Row(x1..=x2, y));
+        }
+        if let Some(caps) = RE_COL.captures(s) {
+            let x = caps.get(1).unwrap().as_str().parse::<i64>().unwrap();
+            let y1 = caps.get(2).unwrap().as_str().parse::<i64>().unwrap();
+            let y2 = caps.get(3).unwrap().as_str().parse::<i64>().unwrap();
+            return Ok(InputItem::Col(x, y1..=y2));
+        }
+        Err("invalid input line".to_string())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WCell {
+    Sand,
+    Clay,
+    WetSand,
+    Water,
+}
+
+struct WrappedGrid<T: Copy + PartialEq> {
+    grid: RefCell<NumberPlane<T>>,
+    y_end: i64,
+}
+impl<T: Copy + PartialEq> WrappedGrid<T> {
+    fn new(grid: NumberPlane<T>, y_end: i64) -> Self {
+        WrappedGrid{ grid: RefCell::new(grid), y_end }
+    }
+    fn get(&self, x:i64, y:i64) -> T {
+        self.grid.borrow().get(x, y)
+    }
+    fn set(&self, x:i64, y:i64, val:T) {
+        self.grid.borrow_mut().set(x, y, val);
+    }
+}
+
+// The ground after the water has settled, with the x and y ranges of the
+// clay.
+fn flood(input: &[InputItem]) -> (NumberPlane<WCell>, Range<i64>, Range<i64>) {
+    let mut grid = NumberPlane::new(WCell::Sand);
+    for item in input.iter() {
+        match item {
+            InputItem::Row(xr, y) =>
+                for x in xr.clone() {
+                    grid.set(x, *y, WCell::Clay);
+                },
+            InputItem::Col(x, yr) =>
+                for y in yr.clone() {
+                    grid.set(*x, y, WCell::Clay);
+                },
+        }
+    }
+    let (xs, ys) = grid.bounding_box().unwrap();
+
+    grid.set(500, ys.start, WCell::WetSand);
+
+    let grid = WrappedGrid::new(grid, ys.end);
+    go_vertical(500, ys.start, &grid);
+    (grid.grid.into_inner(), xs, ys)
+}
+
+fn bothparts(input: &[InputItem]) -> (i64, i64) {
+    let (grid, _, _) = flood(input);
+    let (n_w, n_s) = grid.iter().fold((0, 0), |(n_w, n_s), (_, _, cell)| match cell {
+        WCell::Water => (n_w + 1, n_s),
+        WCell::WetSand => (n_w, n_s + 1),
+        _ => (n_w, n_s),
+    });
+    (n_w + n_s, n_w)
+}
+
+fn go_vertical(start_x:i64, start_y:i64, grid: &WrappedGrid<WCell>) -> bool {
+    let x = start_x;
+    let mut y = start_y;
+    loop {
+        grid.set(x, y, WCell::WetSand);
+        if y + 1 == grid.y_end {
+            return true;
+        }
+        match grid.get(x, y + 1) {
+            WCell::Sand => { y += 1; },
+            WCell::WetSand => { return true; }
+            WCell::Clay | WCell::Water   => { break; },
+        }
+    }
+    loop {
+        if go_horiz(x, y, grid) { return true; }
+        y -= 1;
+        if y <= start_y { return false; }
+    }
+}
+
+fn go_horiz(start_x:i64, start_y:i64, grid: &WrappedGrid<WCell>) -> bool {
+    let mut x = start_x;
+    let y = start_y;
+    grid.set(x, y, WCell::WetSand);
+    let mut unbound = false;
+    // left
+    loop {
+        match grid.get(x - 1, y) {
+            WCell::Clay | WCell::WetSand => { break; },
+            WCell::Water => panic!("did not expect to find water"),
+            _ => (),
+        }
+        x -= 1;
+        match grid.get(x, y + 1) {
+            WCell::Sand | WCell::WetSand =>
+                if go_vertical(x, y, grid) {
+                    unbound = true;
+                    break;
+                },
+            WCell::Clay | WCell::Water =>
+                grid.set(x, y, WCell::WetSand),
+        }
+    }
+    let minx = x;
+
+    x = start_x;
+    // right
+    loop {
+        match grid.get(x + 1, y) {
+            WCell::Clay | WCell::WetSand => { break; },
+            WCell::Water => panic!("did not expect to find water"),
+            _ => (),
+        }
+        x += 1;
+        match grid.get(x, y + 1) {
+            WCell::Sand | WCell::WetSand =>
+                if go_vertical(x, y, grid) {
+                    unbound = true;
+                    break;
+                },
+            WCell::Clay | WCell::Water =>
+                grid.set(x, y, WCell::WetSand),
+        }
+    }
+    if !unbound {
+        for xx in minx ..= x {
+            grid.set(xx, y, WCell::Water);
+        }
+    }
+    unbound
+}
+
+/// The settled water drawn as in the puzzle, one column wider than the clay
+/// on each side.
+pub fn render(input: &[InputItem]) -> String {
+    let (grid, xs, ys) = flood(input);
+    grid.format_area(xs.start - 1 .. xs.end + 1, ys, |c| match c {
+        WCell::Sand => '.',
+        WCell::Water => '~',
+        WCell::Clay => '#',
+        WCell::WetSand => '|',
+    })
+}
+
+pub struct Day17;
+
+impl Solver for Day17 {
+    type Input = Vec<InputItem>;
+    type Part1 = i64;
+    type Part2 = i64;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        bothparts(input).0
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        bothparts(input).1
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        bothparts(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day17_test() {
+        let input: Vec<InputItem> = test_input(include_str!("day17.testinput"));
+        assert_eq!(bothparts(&input), (57, 29));
+        assert_eq!(render(&input), "\
+......|.....#.
+.#..#||||...#.
+.#..#~~#|.....
+.#..#~~#|.....
+.#~~~~~#|.....
+.#~~~~~#|.....
+.#######|.....
+........|.....
+...|||||||||..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#######|..
+");
+    }
+}
@@ -0,0 +1,74 @@
+use crate::vm::{VM, Instruction, Observer, ProgramItem, RunResult, NREGS};
+use crate::days::{lines, Solver};
+
+pub struct Day19;
+
+impl Solver for Day19 {
+    type Input = Vec<ProgramItem>;
+    type Part1 = usize;
+    type Part2 = usize;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
+fn part1(prog: &[ProgramItem]) -> usize {
+    let mut vm = VM::new();
+    vm.load(prog);
+    match vm.run() {
+        RunResult::Err(e) => panic!("Error while running program: {}", e),
+        RunResult::Halt => {return vm.r[0];},
+        _ => (),
+    }
+    panic!();
+}
+
+// Breaks the first time the IP goes backward; by then the program is done
+// initializing the number it wants to factor, which will be in register 4
+struct BackwardJump {
+    last_ip: usize,
+}
+impl Observer for BackwardJump {
+    fn break_before(&mut self, ip: usize, _inst: &Instruction, _regs: &[usize; NREGS]) -> bool {
+        let backward = ip < self.last_ip;
+        self.last_ip = ip;
+        backward
+    }
+}
+
+fn part2(prog: &[ProgramItem]) -> usize {
+    let mut vm = VM::with_observer(BackwardJump { last_ip: 0 });
+    vm.load(prog);
+    vm.r[0] = 1;
+    // The program calculates the sum of all divisors of a large number.
+    // It's too slow to complete in a reasonable amount of time, so we'll figure
+    // out what number it's using and then do it directly instead of by continuing
+    // to run the program.
+    // I can't take credit for figuring that out; I didn't have the patience to
+    // analyze what the program was doing and found it on reddit.
+    match vm.run() {
+        RunResult::Err(e) => panic!("Error while running program: {}", e),
+        RunResult::Halt => return vm.r[0],
+        _ => (),
+    };
+    let bignum = vm.r[4];
+    (1..=bignum).filter(|i| bignum.is_multiple_of(*i)).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day19_test() {
+        let input: Vec<ProgramItem> = test_input(include_str!("day19.testinput"));
+        assert_eq!(part1(&input), 7);
+    }
+}
@@ -0,0 +1,312 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::vec::Vec;
+use crate::days::Solver;
+use crate::search::Search;
+
+#[derive(Debug, PartialEq)]
+enum Node<'a> {
+    Segment(&'a str),
+    Path(Vec<Node<'a>>),
+    Branch(Vec<Node<'a>>),
+}
+impl<'a> Node<'a>{
+    fn from_path(path: Vec<Node<'a>>) -> Self {
+        if path.len() == 1 {
+            if let Node::Segment(s) = path[0] {
+                Node::Segment(s)
+            } else {
+                Node::Path(path)
+            }
+        } else {
+            Node::Path(path)
+        }
+    }
+}
+
+enum Dir { N, E, S, W }
+impl Dir {
+    fn from_char(c: char) -> Self {
+        match c {
+            'N' => Dir::N,
+            'E' => Dir::E,
+            'W' => Dir::W,
+            'S' => Dir::S,
+            _ => panic!(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Room {
+    door_n: bool,
+    door_e: bool,
+    door_w: bool,
+    door_s: bool,
+}
+impl Room {
+    fn new() -> Self {
+        Room { door_n: false, door_e: false, door_w: false, door_s: false }
+    }
+}
+
+struct ElfMap {
+    data: RefCell<HashMap<(i32, i32), Room>>,
+}
+impl ElfMap {
+    fn new() -> Self {
+        let mut r = HashMap::new();
+        r.insert((0, 0), Room::new());
+        Self { data: RefCell::new(r) }
+    }
+    fn move_from(&self, point: (i32, i32), dir: Dir) -> (i32, i32) {
+        let x = point.0;
+        let y = point.1;
+        let mut data = self.data.borrow_mut();
+        let cell: &mut Room = data.get_mut(&point).unwrap();
+        let next = match dir {
+            Dir::N => {
+                cell.door_n = true;
+                (x, y-1)
+            },
+            Dir::E => {
+                cell.door_e = true;
+                (x+1, y)
+            },
+            Dir::W => {
+                cell.door_w = true;
+                (x-1, y)
+            },
+            Dir::S => {
+                cell.door_s = true;
+                (x, y+1)
+            },
+        };
+        let nextcell = data.entry(next).or_insert(Room::new());
+        match dir {
+            Dir::N => nextcell.door_s = true,
+            Dir::S => nextcell.door_n = true,
+            Dir::E => nextcell.door_w = true,
+            Dir::W => nextcell.door_e = true,
+        };
+        next
+    }
+}
+
+pub struct Day20;
+
+impl Solver for Day20 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+    fn parse(input: &str) -> Self::Input {
+        input.trim().to_string()
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        bothparts(input).0
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        bothparts(input).1
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        bothparts(input)
+    }
+}
+
+fn bothparts(input: &str) -> (usize, usize) {
+    let tree = parse(input);
+    let map = ElfMap::new();
+    traverse(&map, &tree, (0, 0));
+    //println!("map has {} rooms", map.data.borrow().len());
+    let mapdata = map.data.borrow();
+    let paths = Search::bfs(|&(x, y): &(i32, i32)| {
+        let room = mapdata[&(x, y)];
+        [(room.door_n, (x, y - 1)), (room.door_e, (x + 1, y)), (room.door_w, (x - 1, y)), (room.door_s, (x, y + 1))]
+            .into_iter()
+            .filter_map(|(door, point)| door.then_some(point))
+    }).from((0, 0));
+    let dists = paths.dists();
+    let maxcost = dists.values().max_by_key(|t| *t).unwrap();
+    let count = dists.values().filter(|t| **t >= 1000).count();
+    (*maxcost, count)
+}
+
+fn traverse(map: &ElfMap, node: &Node, start: (i32, i32)) -> HashSet<(i32, i32)> {
+    let mut starts = HashSet::new();
+    starts.insert(start);
+    match node {
+        Node::Segment(s) => {
+            let mut ends = HashSet::new();
+            for start in starts {
+                let mut loc = start;
+                for c in s.chars() {
+                    let dir = Dir::from_char(c);
+                    loc = map.move_from(loc, dir);
+                }
+                ends.insert(loc);
+            }
+            ends
+        },
+        Node::Path(p) => {
+            for n in p.iter() {
+                let mut ends = HashSet::new();
+                for start in starts {
+                    let nexts = traverse(map, n, start);
+                    ends.extend(&nexts);
+                }
+                starts = ends;
+            }
+            starts
+        },
+        Node::Branch(b) => {
+            let mut ends = HashSet::new();
+            for start in starts {
+                for n in b.iter() {
+                    let nexts = traverse(map, n, start);
+                    ends.extend(&nexts);
+                }
+            }
+            ends
+        },
+    }
+}
+
+fn parse<'a>(input: &'a str) -> Node<'a> {
+    let mut level = 0;
+    let mut start = 0;
+    let mut path: Vec<Node<'a>> = Vec::new();
+    let mut branch: Vec<Node<'a>> = Vec::new();
+    for (idx, c) in input.char_indices() {
+        match c {
+            '^' => start = idx + 1,
+            '(' => {
+                if level == 0 {
+                    if start < idx {
+                        path.push(Node::Segment(&input[start..idx]));
+                    }
+                    start = idx + 1;
+                }
+                level += 1;
+            },
+            ')' => {
+                assert_ne!(level, 0);
+                level -= 1;
+                if level == 0 {
+                    path.push(parse(&input[start..idx]));
+                    start = idx + 1;
+                }
+            },
+            '|' => if level == 0 {
+                if start < idx {
+                    path.push(Node::Segment(&input[start..idx]));
+                }
+                branch.push(Node::from_path(path));
+                path = Vec::new();
+                start = idx + 1;
+            },
+            '$' => {
+                if start < idx {
+                    path.push(Node::Segment(&input[start..idx]));
+                }
+                start = idx + 1;
+            },
+            'N' | 'E' | 'W' | 'S' => (),
+            _ => panic!("invalid character in input"),
+        }
+    }
+    assert_eq!(level, 0);
+    if !input[start..].is_empty() || &input[start-1..start] == "|" {
+        path.push(Node::Segment(&input[start..]));
+    }
+    if !path.is_empty() && !branch.is_empty() {
+        branch.push(Node::from_path(path));
+        path = Vec::new();
+    }
+    if !branch.is_empty() {
+        Node::Branch(branch)
+    } else {
+        Node::from_path(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn node_parse() {
+        if let Node::Segment(foo) = parse("^NEWSNEWS$") {
+            assert_eq!(foo, "NEWSNEWS");
+        } else {
+            panic!();
+        }
+
+        if let Node::Branch(foo) = parse("NEWS|SWEN|EEE") {
+            assert_eq!(foo.len(), 3);
+            assert_eq!(foo[0], Node::Segment("NEWS"));
+            assert_eq!(foo[1], Node::Segment("SWEN"));
+            assert_eq!(foo[2], Node::Segment("EEE"));
+        } else {
+            panic!();
+        }
+
+        let foo = parse("^ENWWW(NEEE|SSE(EE|N))$");
+        assert_eq!(
+            foo,
+            Node::Path(vec![
+                Node::Segment("ENWWW"),
+                Node::Branch(vec![
+                    Node::Segment("NEEE"),
+                    Node::Path(vec![
+                        Node::Segment("SSE"),
+                        Node::Branch(vec![
+                            Node::Segment("EE"),
+                            Node::Segment("N")
+                        ])
+                    ])
+                ])
+            ])
+        );
+
+        let foo = parse("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$");
+        println!("{:?}", foo);
+        assert_eq!(
+            foo,
+            Node::Path(vec![
+                Node::Segment("ENNWSWW"),
+                Node::Branch(vec![
+                    Node::Segment("NEWS"),
+                    Node::Segment(""),
+                ]),
+                Node::Segment("SSSEEN"),
+                Node::Branch(vec![
+                    Node::Segment("WNSE"),
+                    Node::Segment(""),
+                ]),
+                Node::Segment("EE"),
+                Node::Branch(vec![
+                    Node::Segment("SWEN"),
+                    Node::Segment(""),
+                ]),
+                Node::Segment("NNN"),
+            ])
+        );
+    }
+
+    #[test]
+    fn day20_test() {
+        let (part1, _part2) = bothparts("^WNE$");
+        assert_eq!(part1, 3);
+        let (part1, _part2) = bothparts("^ENWWW(NEEE|SSE(EE|N))$");
+        assert_eq!(part1, 10);
+        let (part1, _part2) = bothparts("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$");
+        assert_eq!(part1, 18);
+        let (part1, _part2) = bothparts("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EE(SWEN|)NNN$");
+        assert_eq!(part1, 18);
+        let (part1, _part2) = bothparts("^ESSWWN(E|NNENN(EESS(WNSE|)SSS|WWWSSSSE(SW|NNNE)))$");
+        assert_eq!(part1, 23);
+        let (part1, _part2) = bothparts("^WSSEESWWWNW(S|NENNEEEENN(ESSSSW(NWSW|SSEN)|WSWWN(E|WWS(E|SS))))$");
+        assert_eq!(part1, 31);
+    }
+}
+
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+use std::vec::Vec;
+use crate::vm::{VM, ProgramItem, RunResult};
+use crate::days::{lines, Part, Solver};
+
+// The program only halts once r0 matches the value it compares it with at
+// its one `eqrr`; those values eventually repeat. Returns them in order, up
+// to the first repeat, or just the first one for part 1.
+fn halting_values(prog: &[ProgramItem], part: Part) -> Vec<usize> {
+    let mut values: HashSet<usize> = HashSet::new();
+    let mut order = Vec::new();
+    let mut vm = VM::new();
+    vm.load(prog);
+    vm.set_breakpoint(0);
+    loop {
+        match vm.run() {
+            RunResult::Break(inst) => {
+                assert_eq!(inst.opcode, "eqrr");
+                let target = if inst.a == 0 { vm.r[inst.b] } else { vm.r[inst.a] };
+                if !values.insert(target) {
+                    return order;
+                }
+                order.push(target);
+                if part == Part::One {
+                    return order;
+                }
+            },
+            _ => panic!(),
+        };
+    }
+}
+
+pub struct Day21;
+
+impl Solver for Day21 {
+    type Input = Vec<ProgramItem>;
+    type Part1 = usize;
+    type Part2 = usize;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        halting_values(input, Part::One)[0]
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        *halting_values(input, Part::Two).last().unwrap()
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        let values = halting_values(input, Part::Two);
+        (values[0], *values.last().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn day21_test() {
+        // r1 goes 1, 4, 5, 0, 1, ... and never matches r0
+        let input = Day21::parse("#ip 4\n#reg 0 99\nseti 0 0 1\nmuli 1 3 1\naddi 1 1 1\nbani 1 7 1\neqrr 1 0 2\naddr 2 4 4\nseti 0 0 4\n");
+        assert_eq!(halting_values(&input, Part::Two), vec![1, 4, 5, 0]);
+        assert_eq!(Day21::both(&input), (Day21::part1(&input), Day21::part2(&input)));
+        assert_eq!(Day21::both(&input), (1, 0));
+    }
+}
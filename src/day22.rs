@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use ya_advent_lib::coords::Coord2D;
+use crate::days::{lines, Solver};
+use crate::search::Search;
+
+pub enum Input {
+    Depth(i64),
+    Target(Coord2D),
+}
+
+impl FromStr for Input {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref D_RE: Regex = Regex::new(r"depth: *(\d+)").unwrap();
+        }
+        lazy_static! {
+            static ref T_RE: Regex = Regex::new(r"target: *(\d+),(\d+)").unwrap();
+        }
+        if let Some(caps) = D_RE.captures(s) {
+            let d = caps.get(1).unwrap().as_str().parse::<i64>().unwrap();
+            Ok(Input::Depth(d))
+        }
+        else if let Some(caps) = T_RE.captures(s) {
+            let x = caps.get(1).unwrap().as_str().parse::<i64>().unwrap();
+            let y = caps.get(2).unwrap().as_str().parse::<i64>().unwrap();
+            Ok(Input::Target(Coord2D::new(x, y)))
+        }
+        else {
+            Err(format!("invalid input: {}", s))
+        }
+    }
+}
+
+#[derive(Eq, PartialEq)]
+pub enum CellType {
+    Rocky,
+    Wet,
+    Narrow,
+}
+
+#[derive(Clone, Copy)]
+pub struct CaveCell {
+    pub e_level: i64,
+    pub g_index: i64,
+}
+impl CaveCell {
+    fn new() -> Self { CaveCell{e_level: 0, g_index: 0} }
+    pub fn cell_type(&self) -> CellType {
+        match self.e_level % 3 {
+            0 => CellType::Rocky,
+            1 => CellType::Wet,
+            2 => CellType::Narrow,
+            _ => panic!(),
+        }
+    }
+}
+
+pub struct CaveGrid {
+    cache: HashMap<Coord2D, CaveCell>,
+    depth: i64,
+    target: Coord2D,
+}
+impl CaveGrid {
+    pub fn new(target: Coord2D, depth: i64) -> Self {
+        Self{
+            cache: HashMap::new(),
+            target,
+            depth,
+        }
+    }
+    pub fn get(&mut self, point: Coord2D) -> CaveCell {
+        if self.cache.contains_key(&point) { return self.cache[&point]; }
+        let mut cc = CaveCell::new();
+        if point.x == 0 {
+            cc.g_index = 48271 * point.y;
+        }
+        else if point.y == 0 {
+            cc.g_index = 16807 * point.x;
+        }
+        else if point == self.target {
+            cc.g_index = 0;
+        }
+        else {
+            let c1 = self.get(point + Coord2D::new(-1, 0));
+            let c2 = self.get(point + Coord2D::new(0, -1));
+            cc.g_index = c1.e_level * c2.e_level;
+        }
+        cc.e_level = (cc.g_index + self.depth) % 20183;
+        self.cache.insert(point, cc);
+        cc
+    }
+}
+
+fn setup(input: &[Input]) -> (Coord2D, CaveGrid) {
+    let depth;
+    let target;
+    if let Input::Depth(d) = input[0] {
+        depth = d;
+    } else {
+        panic!("invalid input");
+    }
+    if let Input::Target(t) = input[1] {
+        target = t;
+    } else {
+        panic!("invalid input");
+    }
+    let grid: CaveGrid = CaveGrid::new(target, depth);
+    (target, grid)
+}
+
+fn part1(input: &[Input]) -> i64 {
+    let (target, mut grid) = setup(input);
+    risk(target, &mut grid)
+}
+
+fn part2(input: &[Input]) -> i64 {
+    let (target, mut grid) = setup(input);
+    rescue(target, &mut grid)
+}
+
+// Both parts from one cave, so the second reuses the regions the first
+// already worked out.
+fn bothparts(input: &[Input]) -> (i64, i64) {
+    let (target, mut grid) = setup(input);
+    (risk(target, &mut grid), rescue(target, &mut grid))
+}
+
+fn risk(target: Coord2D, grid: &mut CaveGrid) -> i64 {
+    (0 ..= target.x)
+        .cartesian_product(0 ..= target.y)
+        .map(|(x, y)| grid.get(Coord2D::new(x, y)).e_level % 3)
+        .sum()
+}
+
+fn rescue(target: Coord2D, grid: &mut CaveGrid) -> i64 {
+    let allowed = |cell: &CellType, tool: Tool| !matches!(
+        (cell, tool),
+        (CellType::Rocky, Tool::Neither) | (CellType::Wet, Tool::Torch) | (CellType::Narrow, Tool::Climbing)
+    );
+    let goal = (target, Tool::Torch);
+    let search = Search::dijkstra(|&(point, tool): &(Coord2D, Tool)| {
+        let mut next: Vec<((Coord2D, Tool), i64)> = Vec::with_capacity(6);
+        let cell = grid.get(point).cell_type();
+        for t in [Tool::Neither, Tool::Climbing, Tool::Torch] {
+            if t != tool && allowed(&cell, t) { next.push(((point, t), 7)); }
+        }
+        let mut moves = vec![Coord2D::new(point.x + 1, point.y), Coord2D::new(point.x, point.y + 1)];
+        if point.y > 0 { moves.push(Coord2D::new(point.x, point.y - 1)); }
+        if point.x > 0 { moves.push(Coord2D::new(point.x - 1, point.y)); }
+        for p in moves {
+            if allowed(&grid.get(p).cell_type(), tool) { next.push(((p, tool), 1)); }
+        }
+        next
+    });
+    let paths = search
+        .heuristic(move |(point, _)| point.mdist_to(&target))
+        .tiebreak(|&(point, tool)| (point.y, point.x, tool as u8))
+        .to((Coord2D::new(0, 0), Tool::Torch), |node| *node == goal);
+    paths.dist(&goal).expect("path not found")
+}
+
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+enum Tool {
+    Torch = 0,
+    Climbing,
+    Neither,
+}
+
+pub struct Day22;
+
+impl Solver for Day22 {
+    type Input = Vec<Input>;
+    type Part1 = i64;
+    type Part2 = i64;
+    fn parse(input: &str) -> Self::Input {
+        lines(input)
+    }
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        bothparts(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ya_advent_lib::read::test_input;
+
+    #[test]
+    fn day22_test() {
+        let input: Vec<Input> = test_input("depth: 510\ntarget: 10,10\n");
+        assert_eq!(part1(&input), 114);
+        assert_eq!(part2(&input), 45);
+        assert_eq!(bothparts(&input), (114, 45));
+    }
+}
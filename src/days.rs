@@ -0,0 +1,165 @@
+//! The table of puzzle days, shared by the `advent2018` runner and the
+//! per-day `dayNN` binaries.
+
+use std::any::Any;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::vec::Vec;
+use ya_advent_lib::read::{input_as_string, input_from_iter};
+use crate::*;
+use crate::bench::Timings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// One day's puzzle: the input is parsed once and both parts are answered
+/// from the parsed form.
+pub trait Solver {
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+    fn parse(input: &str) -> Self::Input;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+    /// Both answers, for days that find them in the same pass.
+    fn both(input: &Self::Input) -> (Self::Part1, Self::Part2) {
+        (Self::part1(input), Self::part2(input))
+    }
+}
+
+/// Parses `input` once and answers each of `parts`, for the `run` column of
+/// the table.
+pub fn run<S: Solver>(input: &str, parts: &[Part]) -> Vec<String> {
+    let input = S::parse(input);
+    if parts.contains(&Part::One) && parts.contains(&Part::Two) {
+        let (one, two) = S::both(&input);
+        return parts.iter().map(|part| match part {
+            Part::One => one.to_string(),
+            Part::Two => two.to_string(),
+        }).collect();
+    }
+    parts.iter().map(|part| match part {
+        Part::One => S::part1(&input).to_string(),
+        Part::Two => S::part2(&input).to_string(),
+    }).collect()
+}
+
+pub struct Day {
+    pub day: u32,
+    /// Day 25 only has a first part.
+    pub parts: &'static [Part],
+    /// Parts that take long enough that `all` leaves them out by default.
+    pub slow: &'static [Part],
+    /// One answer per part asked for; see `run`.
+    pub run: fn(&str, &[Part]) -> Vec<String>,
+    /// Times parsing and the given parts; see `bench::solver`.
+    pub bench: fn(&str, &[Part], usize) -> Timings,
+}
+
+macro_rules! day {
+    ($day:expr, $parts:expr, $solver:ty) => {
+        day!($day, $parts, $solver, slow: &[])
+    };
+    ($day:expr, $parts:expr, $solver:ty, slow: $slow:expr) => {
+        Day { day: $day, parts: $parts, slow: $slow, run: run::<$solver>, bench: bench::solver::<$solver> }
+    };
+}
+
+const BOTH: &[Part] = &[Part::One, Part::Two];
+
+pub static DAYS: &[Day] = &[
+    day!(1, BOTH, day01::Day01),
+    day!(2, BOTH, day02::Day02),
+    day!(3, BOTH, day03::Day03),
+    day!(4, BOTH, day04::Day04),
+    day!(5, BOTH, day05::Day05),
+    day!(6, BOTH, day06::Day06),
+    day!(7, BOTH, day07::Day07),
+    day!(8, BOTH, day08::Day08),
+    day!(9, BOTH, day09::Day09),
+    day!(10, BOTH, day10::Day10),
+    day!(11, BOTH, day11::Day11),
+    day!(12, BOTH, day12::Day12),
+    day!(13, BOTH, day13::Day13),
+    day!(14, BOTH, day14::Day14),
+    day!(15, BOTH, day15::Day15),
+    day!(16, BOTH, day16::Day16),
+    day!(17, BOTH, day17::Day17),
+    day!(18, BOTH, day18::Day18),
+    day!(19, BOTH, day19::Day19),
+    day!(20, BOTH, day20::Day20),
+    day!(21, BOTH, day21::Day21, slow: &[Part::Two]),
+    day!(22, BOTH, day22::Day22),
+    day!(23, BOTH, day23::Day23),
+    day!(24, BOTH, day24::Day24),
+    day!(25, &[Part::One], day25::Day25),
+];
+
+pub fn find(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+/// The usual place to look for a day's input when none is given.
+pub fn default_input(day: u32) -> String {
+    format!("day{day:02}.input")
+}
+
+/// What the `dayNN` binaries run: reads the input from the file named on
+/// the command line, stdin, or `dayNN.input`, and prints every part.
+pub fn main(day: u32) {
+    let day = find(day).unwrap();
+    let input = input_as_string();
+    for (part, answer) in day.parts.iter().zip((day.run)(&input, day.parts)) {
+        println!("Part {part}: {answer}");
+    }
+}
+
+// One parsed item per line of the input.
+pub(crate) fn lines<T: FromStr>(input: &str) -> Vec<T>
+where <T as FromStr>::Err: Any {
+    input_from_iter(input.lines().map(String::from))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn table() {
+        assert_eq!(DAYS.iter().map(|d| d.day).collect::<Vec<_>>(), (1..=25).collect::<Vec<_>>());
+        let day = find(3).unwrap();
+        assert_eq!((day.run)(include_str!("day03.testinput"), &[Part::One, Part::Two]), vec!["4", "3"]);
+        assert_eq!((day.run)(include_str!("day03.testinput"), &[Part::Two]), vec!["3"]);
+        assert!(find(26).is_none());
+        assert_eq!(find(21).unwrap().slow, &[Part::Two]);
+        assert!(DAYS.iter().all(|d| d.slow.iter().all(|p| d.parts.contains(p))));
+
+        let samples = "Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n\n\n\n";
+        assert_eq!(day16::Day16::part1(&day16::Day16::parse(samples)), 1);
+        assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+        assert!("3".parse::<Part>().is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Sets of integers kept as ranges rather than as individual points, in one
+//! dimension (`IntervalSet`) and two (`RectSet`).
+
+use std::collections::BTreeMap;
+use std::ops::{Range, RangeInclusive};
+use std::vec::Vec;
+
+/// A set of i64s, stored as sorted half-open ranges that neither overlap nor
+/// touch, so equal sets always have equal representations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn ranges(&self) -> &[Range<i64>] { &self.ranges }
+    pub fn is_empty(&self) -> bool { self.ranges.is_empty() }
+    /// Number of integers in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| (r.end - r.start) as u64).sum()
+    }
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= x);
+        self.ranges.get(i).is_some_and(|r| r.start <= x)
+    }
+    pub fn insert(&mut self, r: Range<i64>) {
+        *self = self.union(&r.into());
+    }
+    pub fn remove(&mut self, r: Range<i64>) {
+        *self = self.difference(&r.into());
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut all: Vec<Range<i64>> = self.ranges.iter().chain(&other.ranges).cloned().collect();
+        all.sort_unstable_by_key(|r| r.start);
+        let mut ranges: Vec<Range<i64>> = Vec::with_capacity(all.len());
+        for r in all {
+            match ranges.last_mut() {
+                Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+                _ => ranges.push(r),
+            }
+        }
+        IntervalSet { ranges }
+    }
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let r = a.start.max(b.start) .. a.end.min(b.end);
+            if !r.is_empty() {
+                ranges.push(r);
+            }
+            if a.end < b.end { i += 1; } else { j += 1; }
+        }
+        IntervalSet { ranges }
+    }
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for a in &self.ranges {
+            let mut start = a.start;
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < a.end {
+                let b = &other.ranges[k];
+                if b.start > start {
+                    ranges.push(start .. b.start);
+                }
+                start = start.max(b.end);
+                k += 1;
+            }
+            if start < a.end {
+                ranges.push(start .. a.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl From<Range<i64>> for IntervalSet {
+    fn from(r: Range<i64>) -> Self {
+        let ranges = if r.is_empty() { Vec::new() } else { vec![r] };
+        IntervalSet { ranges }
+    }
+}
+impl From<RangeInclusive<i64>> for IntervalSet {
+    fn from(r: RangeInclusive<i64>) -> Self {
+        (*r.start() .. r.end() + 1).into()
+    }
+}
+impl FromIterator<Range<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        set.ranges = iter.into_iter().filter(|r| !r.is_empty()).collect();
+        set.union(&IntervalSet::new())
+    }
+}
+
+/// A set of points in the plane, kept as horizontal slabs: each key is the
+/// first row of a slab and maps to the columns covered in every row down to
+/// the next key. The last key always maps to the empty set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RectSet {
+    slabs: BTreeMap<i64, IntervalSet>,
+}
+
+impl RectSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn is_empty(&self) -> bool { self.slabs.is_empty() }
+    /// Number of points in the set, computed from the slabs.
+    pub fn area(&self) -> u64 {
+        self.slabs.iter().zip(self.slabs.keys().skip(1))
+            .map(|((y, xs), next_y)| (next_y - y) as u64 * xs.len())
+            .sum()
+    }
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.slabs.range(..=y).next_back().is_some_and(|(_, xs)| xs.contains(x))
+    }
+    /// Horizontal slabs as `(rows, columns)`, top to bottom, leaving out
+    /// empty ones.
+    pub fn slabs(&self) -> impl Iterator<Item = (Range<i64>, &IntervalSet)> + '_ {
+        self.slabs.iter().zip(self.slabs.keys().skip(1))
+            .filter(|((_, xs), _)| !xs.is_empty())
+            .map(|((y, xs), next_y)| (*y .. *next_y, xs))
+    }
+
+    pub fn insert(&mut self, xs: Range<i64>, ys: Range<i64>) {
+        let xs = xs.into();
+        self.update(ys, |slab| slab.union(&xs));
+    }
+    pub fn remove(&mut self, xs: Range<i64>, ys: Range<i64>) {
+        let xs = xs.into();
+        self.update(ys, |slab| slab.difference(&xs));
+    }
+    pub fn union(&self, other: &RectSet) -> RectSet {
+        let mut result = self.clone();
+        for (ys, xs) in other.slabs() {
+            result.update(ys, |slab| slab.union(xs));
+        }
+        result
+    }
+    pub fn difference(&self, other: &RectSet) -> RectSet {
+        let mut result = self.clone();
+        for (ys, xs) in other.slabs() {
+            result.update(ys, |slab| slab.difference(xs));
+        }
+        result
+    }
+    pub fn intersection(&self, other: &RectSet) -> RectSet {
+        self.difference(&self.difference(other))
+    }
+
+    // Makes `y` the start of a slab.
+    fn split(&mut self, y: i64) {
+        if !self.slabs.contains_key(&y) {
+            let above = self.slabs.range(..y).next_back().map(|(_, xs)| xs.clone()).unwrap_or_default();
+            self.slabs.insert(y, above);
+        }
+    }
+    fn update<F: Fn(&IntervalSet) -> IntervalSet>(&mut self, ys: Range<i64>, f: F) {
+        if ys.is_empty() {
+            return;
+        }
+        self.split(ys.start);
+        self.split(ys.end);
+        for slab in self.slabs.range_mut(ys).map(|(_, xs)| xs) {
+            *slab = f(slab);
+        }
+        // merge slabs that are the same as the one above, and drop leading
+        // empty ones, so equal sets compare equal
+        let mut prev: Option<IntervalSet> = None;
+        self.slabs.retain(|_, xs| {
+            let keep = match &prev {
+                Some(p) => p != xs,
+                None => !xs.is_empty(),
+            };
+            if keep {
+                prev = Some(xs.clone());
+            }
+            keep
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn intervals() {
+        let mut a: IntervalSet = [0..5, 3..8, 10..12, 12..13, 20..20].into_iter().collect();
+        assert_eq!(a.ranges(), &[0..8, 10..13]);
+        assert_eq!(a.len(), 11);
+        assert!(a.contains(0) && a.contains(7) && a.contains(12));
+        assert!(!a.contains(-1) && !a.contains(8) && !a.contains(13));
+
+        let b: IntervalSet = [-5..2, 6..11].into_iter().collect();
+        assert_eq!(a.union(&b), IntervalSet::from(-5..13));
+        assert_eq!(a.intersection(&b).ranges(), &[0..2, 6..8, 10..11]);
+        assert_eq!(a.difference(&b).ranges(), &[2..6, 11..13]);
+        assert_eq!(b.difference(&a).ranges(), &[-5..0, 8..10]);
+
+        a.remove(1..12);
+        assert_eq!(a, [0..1, 12..13].into_iter().collect());
+        a.insert(1..12);
+        assert_eq!(a, IntervalSet::from(0..=12));
+        assert!(IntervalSet::from(3..3).is_empty());
+    }
+
+    #[test]
+    fn rects() {
+        let mut r = RectSet::new();
+        r.insert(0..4, 0..4);
+        r.insert(2..6, 2..6);
+        assert_eq!(r.area(), 28);
+        assert!(r.contains(5, 5) && r.contains(0, 3) && !r.contains(0, 5) && !r.contains(4, 1));
+
+        let mut s = RectSet::new();
+        s.insert(3..10, -10..3);
+        assert_eq!(r.intersection(&s).area(), 5);
+        assert_eq!(r.union(&s).area(), 28 + 91 - 5);
+        assert_eq!(r.difference(&s).area(), 23);
+
+        // the same set built differently compares equal
+        let mut t = RectSet::new();
+        t.insert(0..4, 0..2);
+        t.insert(0..4, 2..4);
+        t.insert(4..6, 2..6);
+        t.insert(2..4, 4..6);
+        assert_eq!(t, r);
+        t.remove(-100..100, -100..100);
+        assert!(t.is_empty());
+        assert_eq!(t.area(), 0);
+    }
+}
@@ -1,39 +1,106 @@
 use std::cmp::{max, PartialEq};
+use std::fmt;
 use std::ops::{Bound, Index, IndexMut, RangeBounds};
 use std::slice::Iter;
+use std::str::FromStr;
 use std::vec::Vec;
 
+pub mod bit_line;
+pub use bit_line::BitLine;
+pub mod sparse;
+pub use sparse::SparseLine;
+
+/// How far a `NumberLine` extends when a write lands outside it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Growth {
+    /// Extend by at least this many slots.
+    Chunk(usize),
+    /// Extend by at least the current length, so that growing in either
+    /// direction is amortised O(1).
+    #[default]
+    Double,
+}
+
+/// The most slots a `NumberLine` will grow to. It stores every index
+/// between its lowest and highest write, so writes that far apart belong
+/// in a `SparseLine` instead.
+pub const MAX_LEN: usize = 1 << 28;
+
+// The first and last non-default indices and how many there are. Values
+// written through `IndexMut` can't be seen as they're written, so it notes
+// the slot it hands out and whether that held a non-default value. The next
+// write settles the difference; queries in between work it out without
+// storing it, which keeps `NumberLine` free of interior mutability.
+#[derive(Clone, Copy)]
+struct Tracking {
+    first: i64,
+    last: i64,
+    count: usize,
+    pending: Option<(i64, bool)>,
+    /// Everything needs recounting, e.g. after `enumerate_mut`.
+    stale: bool,
+}
+impl Tracking {
+    fn stale() -> Self {
+        Self { first: 0, last: 0, count: 0, pending: None, stale: true }
+    }
+}
+
+/// Values over a contiguous run of indices, growing to cover whatever is
+/// written. Reads outside the run give the default. For writes scattered
+/// over a huge span, use `SparseLine`.
+///
+/// Values only need to be `Clone`, but every bulk copy or fill (growth,
+/// `from_initial`, `trim`) goes through `to_vec`, `extend_from_slice`,
+/// `resize` or `drain`, which come down to plain memory copies and fills
+/// for `Copy` values, so lines of `bool` or `char` cost no more than they
+/// did when `Copy` was required.
 #[derive(Clone)]
-pub struct NumberLine<T: Copy + PartialEq> {
+pub struct NumberLine<T: Clone + PartialEq> {
     min_idx: i64,
     max_idx: i64,
     data: Vec<T>,
     default_val: T,
+    growth: Growth,
+    tracking: Tracking,
 }
-impl<T: Copy + PartialEq> NumberLine<T> {
+impl<T: Clone + PartialEq> NumberLine<T> {
     pub fn new(min_idx: i64, max_idx: i64, default_val: T) -> Self {
         assert!(max_idx >= min_idx);
         Self {
             min_idx,
             max_idx,
-            data: vec![default_val; (max_idx - min_idx + 1) as usize],
+            data: vec![default_val.clone(); (max_idx - min_idx + 1) as usize],
             default_val,
+            growth: Growth::default(),
+            tracking: Tracking { stale: false, ..Tracking::stale() },
         }
     }
     pub fn from_initial(initial: &[T], default_val: T) -> Self {
-        let mut data: Vec<T> = vec![default_val; initial.len()];
-        data.copy_from_slice(initial);
-        Self {
+        // to_vec is a plain memcpy for Copy types
+        let data = initial.to_vec();
+        let mut line = Self {
             min_idx: 0,
             max_idx: data.len() as i64 - 1,
             data,
             default_val,
-        }
+            growth: Growth::default(),
+            tracking: Tracking::stale(),
+        };
+        line.settle();
+        line
+    }
+    pub fn with_growth(mut self, growth: Growth) -> Self {
+        self.growth = growth;
+        self
     }
+    pub fn set_growth(&mut self, growth: Growth) { self.growth = growth; }
     pub fn len(&self) -> usize { self.data.len() }
     pub fn is_empty(&self) -> bool { self.data.is_empty() }
     pub fn start_index(&self) -> i64 { self.min_idx }
     pub fn end_index(&self) -> i64 { self.max_idx + 1 }
+    /// What reads outside the stored range give.
+    pub fn default_val(&self) -> T { self.default_val.clone() }
     /*
     pub fn clone(&self) -> Self {
         let mut data: Vec<T> = vec![self.default_val; self.data.len()];
@@ -46,12 +113,192 @@ impl<T: Copy + PartialEq> NumberLine<T> {
         }
     }
     */
-    pub fn iter(&self) -> Iter<T> { self.data.iter() }
-    pub fn enumerate(&self) -> NumberLineEnumerator<T> {
+    pub fn iter(&self) -> Iter<'_, T> { self.data.iter() }
+    /// Every index in `r` with its value; an unbounded end stops at the end
+    /// of the stored range.
+    pub fn range<R: RangeBounds<i64>>(&self, r: R) -> impl Iterator<Item = (i64, T)> + '_ {
+        let start = match r.start_bound() {
+            Bound::Included(s) => *s,
+            Bound::Excluded(s) => s + 1,
+            Bound::Unbounded => self.min_idx,
+        };
+        let end = match r.end_bound() {
+            Bound::Included(e) => e + 1,
+            Bound::Excluded(e) => *e,
+            Bound::Unbounded => self.max_idx + 1,
+        };
+        (start .. end).map(move |idx| (idx, self[idx].clone()))
+    }
+    /// The whole stored range, untrimmed, since values may be changed to or
+    /// from the default along the way. The bounds are recounted by the next
+    /// write; until then each query recounts them.
+    pub fn enumerate_mut(&mut self) -> impl Iterator<Item = (i64, &mut T)> {
+        self.tracking = Tracking::stale();
+        let min_idx = self.min_idx;
+        self.data.iter_mut().enumerate().map(move |(i, v)| (min_idx + i as i64, v))
+    }
+    /// Every run of `k` consecutive values that includes a non-default one,
+    /// with the index of its first value.
+    pub fn windows(&self, k: usize) -> impl Iterator<Item = (i64, Vec<T>)> + '_ {
+        let (first, last) = match self.occupied() {
+            Some((f, l)) => (self.min_idx + f as i64, self.min_idx + l as i64),
+            None => (0, -(k as i64)),
+        };
+        (first - k as i64 + 1 ..= last)
+            .map(move |start| (start, (start .. start + k as i64).map(|idx| self[idx].clone()).collect()))
+    }
+    /// The values from the first non-default one to the last, one char each.
+    pub fn format_with<F: Fn(T) -> char>(&self, f: F) -> String {
+        self.enumerate().map(|(_, v)| f(v)).collect()
+    }
+    /// One value per char, starting at index 0.
+    pub fn parse_with<F>(s: &str, default_val: T, f: F) -> Result<Self, String>
+            where F: Fn(char) -> Option<T> {
+        let data = s.chars()
+            .map(|c| f(c).ok_or_else(|| format!("invalid char: {c}")))
+            .collect::<Result<Vec<T>, String>>()?;
+        Ok(Self::from_initial(&data, default_val))
+    }
+    /// Drops the default-valued margins at both ends. Reads are unchanged.
+    pub fn trim(&mut self) {
+        let Some(last) = self.data.iter().rposition(|v| *v != self.default_val) else {
+            self.data.clear();
+            self.max_idx = self.min_idx - 1;
+            return;
+        };
+        let first = self.data.iter().position(|v| *v != self.default_val).unwrap();
+        self.data.truncate(last + 1);
+        self.data.drain(..first);
+        self.min_idx += first as i64;
+        self.max_idx = self.min_idx + self.data.len() as i64 - 1;
+    }
+    /// Trims, then releases the memory the margins used.
+    pub fn shrink_to_fit(&mut self) {
+        self.trim();
+        self.data.shrink_to_fit();
+    }
+    // Number of slots to add when `needed` more are required to reach
+    // `idx`, never going past `MAX_LEN`.
+    fn extension(&self, idx: i64, needed: i64) -> i64 {
+        let room = MAX_LEN as i64 - self.data.len() as i64;
+        if needed > room {
+            panic!("NumberLine from {} to {} can't grow to reach {idx}: that needs more than {MAX_LEN} slots, use SparseLine",
+                   self.min_idx, self.max_idx);
+        }
+        let ext = match self.growth {
+            Growth::Chunk(n) => max(needed, n as i64),
+            Growth::Double => max(needed, self.data.len() as i64),
+        };
+        ext.min(room)
+    }
+    pub fn enumerate(&self) -> NumberLineEnumerator<'_, T> {
         NumberLineEnumerator::new(self)
     }
+    /// Like `enumerate`, without cloning each value.
+    pub fn enumerate_ref(&self) -> impl Iterator<Item = (i64, &T)> + '_ {
+        let (first, end) = self.occupied().map_or((0, 0), |(f, l)| (f, l + 1));
+        let min_idx = self.min_idx;
+        self.data[first .. end].iter().enumerate()
+            .map(move |(i, v)| (min_idx + (first + i) as i64, v))
+    }
+    // The tracking with any pending write or recount applied.
+    fn settled(&self) -> Tracking {
+        let mut t = self.tracking;
+        let non_default = |idx: &i64| self[*idx] != self.default_val;
+        if t.stale {
+            t = Tracking { stale: false, ..t };
+            t.count = self.data.iter().filter(|v| **v != self.default_val).count();
+            if let Some(last) = self.data.iter().rposition(|v| *v != self.default_val) {
+                let first = self.data.iter().position(|v| *v != self.default_val).unwrap();
+                t.first = self.min_idx + first as i64;
+                t.last = self.min_idx + last as i64;
+            }
+        }
+        else if let Some((idx, old)) = t.pending.take() {
+            match (old, non_default(&idx)) {
+                (false, true) => {
+                    (t.first, t.last) = if t.count == 0 { (idx, idx) } else { (t.first.min(idx), t.last.max(idx)) };
+                    t.count += 1;
+                },
+                (true, false) => {
+                    t.count -= 1;
+                    // clearing an end means looking for the new one
+                    if t.count > 0 && idx == t.first {
+                        t.first = (idx + 1 ..= t.last).find(non_default).unwrap();
+                    }
+                    if t.count > 0 && idx == t.last {
+                        t.last = (t.first .. idx).rev().find(non_default).unwrap();
+                    }
+                },
+                _ => (),
+            }
+        }
+        t
+    }
+    fn settle(&mut self) {
+        self.tracking = self.settled();
+    }
+    /// The lowest index holding a non-default value. O(1) unless the
+    /// previous lowest one has just been cleared, or `enumerate_mut` has
+    /// been used since the last write.
+    pub fn first_set(&self) -> Option<i64> {
+        let t = self.settled();
+        (t.count > 0).then_some(t.first)
+    }
+    /// The highest index holding a non-default value; see `first_set`.
+    pub fn last_set(&self) -> Option<i64> {
+        let t = self.settled();
+        (t.count > 0).then_some(t.last)
+    }
+    pub fn count_non_default(&self) -> usize {
+        self.settled().count
+    }
+    // Positions in `data` of the first and last non-default values.
+    fn occupied(&self) -> Option<(usize, usize)> {
+        let t = self.settled();
+        (t.count > 0).then(|| ((t.first - self.min_idx) as usize, (t.last - self.min_idx) as usize))
+    }
+    /// The line split into its translation-free shape and the index the
+    /// shape starts at (0 for an all-default line).
+    pub fn canonical(&self) -> (Pattern<T>, i64) {
+        let (cells, offset) = match self.occupied() {
+            Some((first, last)) => (self.data[first ..= last].to_vec(), self.min_idx + first as i64),
+            None => (Vec::new(), 0),
+        };
+        (Pattern { cells, default_val: self.default_val.clone() }, offset)
+    }
+    pub fn pattern(&self) -> Pattern<T> { self.canonical().0 }
+    /// How far `self` has to move to become `other`, if they're translates
+    /// of each other.
+    pub fn translation_to(&self, other: &NumberLine<T>) -> Option<i64> {
+        let (a, a_off) = self.canonical();
+        let (b, b_off) = other.canonical();
+        (a == b).then_some(b_off - a_off)
+    }
 }
-impl<T: Copy + PartialEq> Index<i64> for NumberLine<T> {
+
+/// The non-default stretch of a `NumberLine` without its position, so that
+/// lines which are translates of each other have equal patterns.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern<T> {
+    cells: Vec<T>,
+    default_val: T,
+}
+impl<T: Clone + PartialEq> Pattern<T> {
+    /// From the first non-default value to the last.
+    pub fn cells(&self) -> &[T] { &self.cells }
+    pub fn default_val(&self) -> T { self.default_val.clone() }
+    /// The pattern placed with its first cell at `offset`.
+    pub fn to_line(&self, offset: i64) -> NumberLine<T> {
+        let mut line = NumberLine::from_initial(&self.cells, self.default_val.clone());
+        line.min_idx += offset;
+        line.max_idx += offset;
+        line.tracking.first += offset;
+        line.tracking.last += offset;
+        line
+    }
+}
+impl<T: Clone + PartialEq> Index<i64> for NumberLine<T> {
     type Output = T;
     fn index(&self, idx: i64) -> &Self::Output {
         if idx < self.min_idx || idx > self.max_idx {
@@ -62,64 +309,93 @@ impl<T: Copy + PartialEq> Index<i64> for NumberLine<T> {
         }
     }
 }
-impl<T: Copy + PartialEq> IndexMut<i64> for NumberLine<T> {
+impl<T: Clone + PartialEq> IndexMut<i64> for NumberLine<T> {
     fn index_mut(&mut self, idx: i64) -> &mut Self::Output {
+        self.settle();
+        self.tracking.pending = Some((idx, self[idx] != self.default_val));
         let datalen = self.data.len() as i64;
         if idx < self.min_idx {
-            let ext = max(self.min_idx - idx, 32);
-            let newlen = datalen + ext;
-            self.data.resize(newlen as usize, self.default_val);
-            self.data.copy_within(0 .. (newlen - ext) as usize, ext as usize);
-            for i in 0 .. ext as usize {
-                self.data[i] = self.default_val
-            }
+            let ext = self.extension(idx, self.min_idx - idx);
+            let mut data = Vec::with_capacity(self.data.len() + ext as usize);
+            data.resize(ext as usize, self.default_val.clone());
+            data.extend_from_slice(&self.data);
+            self.data = data;
             self.min_idx -= ext;
         }
         else if idx - self.min_idx >= datalen {
-            let ext = max(idx - self.min_idx - datalen + 1, 32);
-            let newlen = datalen + ext;
-            self.data.resize(newlen as usize, self.default_val);
+            let ext = self.extension(idx, idx - self.min_idx - datalen + 1);
+            self.data.resize((datalen + ext) as usize, self.default_val.clone());
             self.max_idx += ext;
         }
         &mut self.data[(idx - self.min_idx) as usize]
     }
 }
-impl<T: Copy + PartialEq> RangeBounds<i64> for NumberLine<T> {
+/// An empty line whose default is `T::default()`.
+impl<T: Clone + PartialEq + Default> Default for NumberLine<T> {
+    fn default() -> Self {
+        Self::from_initial(&[], T::default())
+    }
+}
+impl<T: Clone + PartialEq + Default> FromIterator<(i64, T)> for NumberLine<T> {
+    fn from_iter<I: IntoIterator<Item = (i64, T)>>(iter: I) -> Self {
+        let mut line = Self::default();
+        line.extend(iter);
+        line
+    }
+}
+impl<T: Clone + PartialEq> Extend<(i64, T)> for NumberLine<T> {
+    fn extend<I: IntoIterator<Item = (i64, T)>>(&mut self, iter: I) {
+        for (idx, val) in iter {
+            self[idx] = val;
+        }
+    }
+}
+/// Like `format_with`, for types that know their own char.
+impl<T: Clone + PartialEq + Into<char>> fmt::Display for NumberLine<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format_with(Into::into))
+    }
+}
+/// Like `parse_with`, for types that know their own char.
+impl<T: Clone + PartialEq + Default + TryFrom<char>> FromStr for NumberLine<T> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, T::default(), |c| T::try_from(c).ok())
+    }
+}
+impl<T: Clone + PartialEq> RangeBounds<i64> for NumberLine<T> {
     fn start_bound(&self) -> Bound<&i64> { Bound::Included(&self.min_idx) }
     fn end_bound(&self) -> Bound<&i64> { Bound::Included(&self.max_idx) }
 }
 
-pub struct NumberLineEnumerator<'a, T: Copy + PartialEq> {
+/// Goes from the first non-default value to the last, defaults in between
+/// included. On an all-default line it yields nothing.
+pub struct NumberLineEnumerator<'a, T: Clone + PartialEq> {
     obj: &'a NumberLine<T>,
     idx: i64,
     max_idx: i64,
 }
-impl<'a, T: Copy + PartialEq> NumberLineEnumerator<'a, T> {
+impl<'a, T: Clone + PartialEq> NumberLineEnumerator<'a, T> {
     pub fn new(obj: &'a NumberLine<T>) -> Self {
-        let mut min_idx = obj.min_idx;
-        let mut max_idx = obj.min_idx - 1;
-        if let Some(end) = obj.iter().rev().enumerate()
-                              .find(|x| *(x.1) != obj.default_val) {
-            max_idx = obj.min_idx + (obj.data.len() - end.0 - 1) as i64;
-            while obj[min_idx] == obj.default_val {
-                min_idx += 1;
-            }
-        }
+        let (first, last) = match (obj.first_set(), obj.last_set()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => (0, -1),
+        };
         Self {
             obj,
-            idx: min_idx,
-            max_idx,
+            idx: first,
+            max_idx: last,
         }
     }
 }
-impl<T: Copy + PartialEq> Iterator for NumberLineEnumerator<'_, T> {
+impl<T: Clone + PartialEq> Iterator for NumberLineEnumerator<'_, T> {
     type Item = (i64, T);
     fn next(&mut self) -> Option<Self::Item> {
         if self.idx > self.max_idx {
             None
         }
         else {
-            let r = (self.idx, self.obj[self.idx]);
+            let r = (self.idx, self.obj[self.idx].clone());
             self.idx += 1;
             Some(r)
         }
@@ -128,11 +404,13 @@ impl<T: Copy + PartialEq> Iterator for NumberLineEnumerator<'_, T> {
 
 #[cfg(test)]
 mod test {
-    use super::NumberLine;
+    use std::collections::HashSet;
+    use std::ops::RangeBounds;
+    use super::{Growth, NumberLine};
 
     #[test]
     fn numberline() {
-        let mut foo: NumberLine<bool> = NumberLine::new(1, 10, false);
+        let mut foo: NumberLine<bool> = NumberLine::new(1, 10, false).with_growth(Growth::Chunk(32));
         assert!(!foo[0]);
         assert!(!foo[1]);
         assert!(!foo[10]);
@@ -167,4 +445,161 @@ mod test {
         assert!(!foo[21]);
         assert_eq!(foo.len(), 74);
     }
+
+    #[test]
+    fn growth() {
+        let mut foo = NumberLine::new(0, 9, 0);
+        foo[-1] = 1;
+        assert_eq!((foo.start_index(), foo.len()), (-10, 20));
+        foo[25] = 2;
+        assert_eq!((foo.start_index(), foo.len()), (-10, 40));
+        // one element at a time leftwards only reallocates log(n) times
+        let mut grows = 0;
+        for i in (-1000..-10).rev() {
+            let len = foo.len();
+            foo[i] = 3;
+            grows += (foo.len() != len) as usize;
+        }
+        assert_eq!(grows, 5);
+
+        foo.trim();
+        assert_eq!((foo.start_index(), foo.end_index()), (-1000, 26));
+        assert_eq!(foo[-1], 1);
+        assert_eq!(foo[25], 2);
+        assert_eq!(foo[-1001], 0);
+        foo.shrink_to_fit();
+        assert_eq!(foo.len(), 1026);
+
+        let mut bar = NumberLine::new(-5, 5, false);
+        bar.trim();
+        assert!(bar.is_empty());
+        assert_eq!(bar.enumerate().next(), None);
+        bar[7] = true;
+        assert!(bar[7]);
+        assert_eq!(bar.enumerate().collect::<Vec<_>>(), vec![(7, true)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "use SparseLine")]
+    fn huge_growth() {
+        let mut foo = NumberLine::new(0, 9, 0u8);
+        foo[1 << 40] = 1;
+    }
+
+    #[test]
+    fn canonical() {
+        let mut foo = NumberLine::new(0, 9, '.');
+        foo[3] = '#';
+        foo[5] = 'x';
+        let mut bar = NumberLine::from_initial(&['#', '.', 'x', '.'], '.');
+        bar[-50] = '#';
+        bar[-50] = '.';
+        assert_eq!(foo.canonical(), (bar.pattern(), 3));
+        assert_eq!(foo.pattern().cells(), &['#', '.', 'x']);
+        assert_eq!(bar.translation_to(&foo), Some(3));
+        assert_eq!(foo.translation_to(&bar), Some(-3));
+        assert_eq!(foo.pattern().to_line(-7).translation_to(&foo), Some(10));
+
+        let patterns: HashSet<_> = [foo.pattern(), bar.pattern()].into_iter().collect();
+        assert_eq!(patterns.len(), 1);
+
+        bar[6] = 'x';
+        assert_eq!(bar.translation_to(&foo), None);
+        let empty = NumberLine::new(-3, 3, '.');
+        assert_eq!(empty.canonical().1, 0);
+        assert_eq!(empty.default_val(), '.');
+        assert_eq!(empty.translation_to(&NumberLine::from_initial(&[], '.')), Some(0));
+        assert_eq!(empty.enumerate().next(), None);
+    }
+
+    #[test]
+    fn collection() {
+        let mut foo = NumberLine::parse_with("#..#", false, |c| Some(c == '#')).unwrap();
+        assert!(NumberLine::parse_with("#x", false, |c| (c != 'x').then_some(true)).is_err());
+        assert_eq!(foo.range(-2..2).collect::<Vec<_>>(),
+                   vec![(-2, false), (-1, false), (0, true), (1, false)]);
+        assert_eq!(foo.range(2..).count(), 2);
+        assert_eq!(foo.range(..=0).map(|x| x.0).collect::<Vec<_>>(), vec![0]);
+
+        for (idx, val) in foo.enumerate_mut() {
+            *val = idx % 2 == 1;
+        }
+        assert_eq!(foo.format_with(|v| if v { '#' } else { '.' }), "#.#");
+
+        let windows: Vec<_> = foo.windows(3).collect();
+        assert_eq!(windows.first(), Some(&(-1, vec![false, false, true])));
+        assert_eq!(windows.last(), Some(&(3, vec![true, false, false])));
+        assert_eq!(windows.len(), 5);
+        assert_eq!(NumberLine::new(0, 5, false).windows(3).next(), None);
+
+        let mut bar: NumberLine<u8> = [(-3, 1), (2, 2)].into_iter().collect();
+        assert_eq!(bar.enumerate().collect::<Vec<_>>(),
+                   vec![(-3, 1), (-2, 0), (-1, 0), (0, 0), (1, 0), (2, 2)]);
+        bar.extend([(10, 3)]);
+        assert_eq!(bar[10], 3);
+
+        let baz: NumberLine<char> = "..ab.".parse().unwrap();
+        assert_eq!(baz[2], 'a');
+        assert_eq!(baz.to_string(), "..ab.");
+    }
+
+    #[test]
+    fn tracking() {
+        let mut foo = NumberLine::new(0, 9, 0);
+        assert_eq!((foo.first_set(), foo.last_set(), foo.count_non_default()), (None, None, 0));
+        assert_eq!(foo.enumerate().next(), None);
+        foo[5] = 1;
+        foo[-20] = 2;
+        foo[7] = 3;
+        foo[7] = 4;
+        assert_eq!((foo.first_set(), foo.last_set(), foo.count_non_default()), (Some(-20), Some(7), 3));
+        foo[-20] = 0;
+        assert_eq!(foo.first_set(), Some(5));
+        foo[7] = 0;
+        foo[6] = 0;
+        assert_eq!((foo.first_set(), foo.last_set(), foo.count_non_default()), (Some(5), Some(5), 1));
+        foo[5] = 0;
+        assert_eq!((foo.first_set(), foo.last_set(), foo.count_non_default()), (None, None, 0));
+        assert_eq!(foo.enumerate().next(), None);
+
+        for (idx, val) in foo.enumerate_mut() {
+            *val = (idx % 4 == 0) as u8;
+        }
+        assert_eq!(foo.count_non_default(), foo.iter().filter(|v| **v != 0).count());
+        assert_eq!(foo.first_set(), Some(foo.start_index()));
+
+        let mut bar = NumberLine::from_initial(&[0, 1, 1, 0], 0);
+        assert_eq!((bar.first_set(), bar.last_set()), (Some(1), Some(2)));
+        bar.trim();
+        bar[1] += 5;
+        assert_eq!(bar.enumerate().collect::<Vec<_>>(), vec![(1, 6), (2, 1)]);
+        assert_eq!(bar.count_non_default(), 2);
+
+        fn shareable<T: Sync>(_: &T) {}
+        shareable(&bar);
+        let moved = NumberLine::from_initial(&[1, 0, 2], 0).canonical().0.to_line(-4);
+        assert_eq!((moved.first_set(), moved.last_set()), (Some(-4), Some(-2)));
+    }
+
+    #[test]
+    fn owned() {
+        let mut units: NumberLine<Vec<&str>> = NumberLine::default();
+        units[3].push("elf");
+        units[-4].push("goblin");
+        units[3].push("goblin");
+        assert_eq!(units[3], vec!["elf", "goblin"]);
+        assert!(units[0].is_empty());
+        assert_eq!(units.count_non_default(), 2);
+        assert_eq!((units.first_set(), units.last_set()), (Some(-4), Some(3)));
+        assert_eq!(units.enumerate_ref().filter(|(_, u)| !u.is_empty()).map(|(i, u)| (i, u.len())).collect::<Vec<_>>(),
+                   vec![(-4, 1), (3, 2)]);
+        assert_eq!(units.enumerate().count(), 8);
+        assert_eq!(units.range(2..4).nth(1), Some((3, vec!["elf", "goblin"])));
+        assert!(units.contains(&-4) && units.contains(&3));
+
+        units[-4].clear();
+        assert_eq!(units.first_set(), Some(3));
+        let names: NumberLine<String> = [(1, "a".to_string()), (2, "b".to_string())].into_iter().collect();
+        assert_eq!(names.canonical().0.cells(), &["a".to_string(), "b".to_string()]);
+    }
 }
@@ -0,0 +1,281 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::{BitAnd, BitOr, BitXor, Index};
+use std::vec::Vec;
+
+const BITS: i64 = u64::BITS as i64;
+
+/// A `NumberLine<bool>` packed 64 pots to a word. Reads outside the stored
+/// range are false and `set` grows the line as needed, but since a bit
+/// can't be borrowed mutably, writes go through `set` instead of `IndexMut`.
+///
+/// Words are aligned so that word `w` always holds indices `64w .. 64w+64`,
+/// which keeps shifts and the bitwise ops between lines cheap.
+#[derive(Clone, Debug, Default)]
+pub struct BitLine {
+    first_word: i64,
+    words: Vec<u64>,
+}
+
+fn word_of(idx: i64) -> i64 {
+    idx.div_euclid(BITS)
+}
+fn bit_of(idx: i64) -> u32 {
+    idx.rem_euclid(BITS) as u32
+}
+
+impl BitLine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Pots `0 .. initial.len()`.
+    pub fn from_initial(initial: &[bool]) -> Self {
+        let mut line = Self::new();
+        for (idx, val) in initial.iter().enumerate() {
+            line.set(idx as i64, *val);
+        }
+        line
+    }
+    pub fn start_index(&self) -> i64 { self.first_word * BITS }
+    pub fn end_index(&self) -> i64 { (self.first_word + self.words.len() as i64) * BITS }
+
+    pub fn get(&self, idx: i64) -> bool {
+        let w = word_of(idx) - self.first_word;
+        w >= 0 && (w as usize) < self.words.len() && self.words[w as usize] & (1 << bit_of(idx)) != 0
+    }
+    pub fn set(&mut self, idx: i64, val: bool) {
+        if !val && !self.get(idx) {
+            return;
+        }
+        let w = self.grow_to(word_of(idx));
+        if val {
+            self.words[w] |= 1 << bit_of(idx);
+        } else {
+            self.words[w] &= !(1 << bit_of(idx));
+        }
+    }
+    // Makes room for absolute word `w`, at least doubling the storage when
+    // it has to grow so that extending either end is amortised O(1).
+    fn grow_to(&mut self, w: i64) -> usize {
+        let len = self.words.len() as i64;
+        if len == 0 {
+            self.first_word = w;
+            self.words.push(0);
+        }
+        else if w < self.first_word {
+            let ext = (self.first_word - w).max(len);
+            self.words.splice(0..0, std::iter::repeat_n(0, ext as usize));
+            self.first_word -= ext;
+        }
+        else if w >= self.first_word + len {
+            let ext = (w - self.first_word - len + 1).max(len);
+            self.words.resize((len + ext) as usize, 0);
+        }
+        (w - self.first_word) as usize
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+    pub fn first_set(&self) -> Option<i64> {
+        self.words.iter().enumerate().find(|(_, w)| **w != 0)
+            .map(|(i, w)| (self.first_word + i as i64) * BITS + w.trailing_zeros() as i64)
+    }
+    pub fn last_set(&self) -> Option<i64> {
+        self.words.iter().enumerate().rev().find(|(_, w)| **w != 0)
+            .map(|(i, w)| (self.first_word + i as i64) * BITS + (BITS - 1 - w.leading_zeros() as i64))
+    }
+    /// Indices of the set pots, in order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = i64> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, w)| {
+            let base = (self.first_word + i as i64) * BITS;
+            let mut w = *w;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    return None;
+                }
+                let bit = w.trailing_zeros();
+                w &= w - 1;
+                Some(base + bit as i64)
+            })
+        })
+    }
+
+    /// The line moved `by` places to the right (left if negative).
+    pub fn shifted(&self, by: i64) -> Self {
+        let q = word_of(by);
+        let r = bit_of(by);
+        let mut words = vec![0u64; self.words.len() + 1];
+        for (i, w) in self.words.iter().enumerate() {
+            words[i] |= w << r;
+            if r > 0 {
+                words[i + 1] |= w >> (u64::BITS - r);
+            }
+        }
+        Self { first_word: self.first_word + q, words }
+    }
+    /// The pots set here but not in `other`.
+    pub fn and_not(&self, other: &BitLine) -> Self {
+        self.zip(other, |a, b| a & !b)
+    }
+    fn zip(&self, other: &BitLine, f: impl Fn(u64, u64) -> u64) -> Self {
+        if self.words.is_empty() && other.words.is_empty() {
+            return Self::new();
+        }
+        let (a, b) = (self.word_range(), other.word_range());
+        let first = a.0.min(b.0);
+        let end = a.1.max(b.1);
+        let words = (first..end).map(|w| f(self.word(w), other.word(w))).collect();
+        Self { first_word: first, words }
+    }
+    fn word_range(&self) -> (i64, i64) {
+        if self.words.is_empty() {
+            (i64::MAX, i64::MIN)
+        } else {
+            (self.first_word, self.first_word + self.words.len() as i64)
+        }
+    }
+    fn word(&self, w: i64) -> u64 {
+        let i = w - self.first_word;
+        if i >= 0 && (i as usize) < self.words.len() { self.words[i as usize] } else { 0 }
+    }
+    // Every pot from `start` to `end` inclusive.
+    fn ones(start: i64, end: i64) -> Self {
+        let (first, last) = (word_of(start), word_of(end));
+        let mut words = vec![u64::MAX; (last - first + 1) as usize];
+        words[0] &= u64::MAX << bit_of(start);
+        *words.last_mut().unwrap() &= u64::MAX >> (BITS as u32 - 1 - bit_of(end));
+        Self { first_word: first, words }
+    }
+
+    /// The occupied window moved so that its first set pot is at 0, along
+    /// with where that pot was. Two lines have the same pattern exactly when
+    /// they're translates of each other.
+    pub fn pattern(&self) -> Option<(i64, Vec<u64>)> {
+        let first = self.first_set()?;
+        let mut words = self.shifted(-first).words;
+        let lead = words.iter().take_while(|w| **w == 0).count();
+        words.drain(..lead);
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+        Some((first, words))
+    }
+    /// A hash of the occupied window that ignores where it sits on the line.
+    pub fn window_hash(&self) -> u64 {
+        let mut h = DefaultHasher::new();
+        self.pattern().map(|p| p.1).hash(&mut h);
+        h.finish()
+    }
+
+    /// One generation of a 1D automaton with the given neighbourhood
+    /// `radius`. `rule` gets the neighbourhood as a number, with the
+    /// leftmost pot in the most significant bit (as day12 writes them), and
+    /// says whether the centre pot is set next. Each neighbourhood the rule
+    /// accepts is matched against whole words at once. The empty
+    /// neighbourhood must map to false, or the line would fill up forever.
+    pub fn step_rule(&self, radius: u32, rule: impl Fn(u32) -> bool) -> Self {
+        assert!(!rule(0), "rule must map the empty neighbourhood to false");
+        let (Some(first), Some(last)) = (self.first_set(), self.last_set()) else {
+            return Self::new();
+        };
+        let r = radius as i64;
+        let width = 2 * radius + 1;
+        let window = Self::ones(first - r, last + r);
+        // neighbours[j][idx] is the pot at idx + j - radius
+        let neighbours: Vec<BitLine> = (0..width as i64).map(|j| self.shifted(r - j)).collect();
+        let mut next = Self::new();
+        for code in (1..1u32 << width).filter(|c| rule(*c)) {
+            let mut term = window.clone();
+            for (j, n) in neighbours.iter().enumerate() {
+                term = if code >> (width - 1 - j as u32) & 1 == 1 { &term & n } else { term.and_not(n) };
+            }
+            next = &next | &term;
+        }
+        next
+    }
+}
+
+impl Index<i64> for BitLine {
+    type Output = bool;
+    fn index(&self, idx: i64) -> &bool {
+        if self.get(idx) { &true } else { &false }
+    }
+}
+
+impl PartialEq for BitLine {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern() == other.pattern()
+    }
+}
+impl Eq for BitLine {}
+impl Hash for BitLine {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pattern().hash(state);
+    }
+}
+
+impl BitAnd for &BitLine {
+    type Output = BitLine;
+    fn bitand(self, other: &BitLine) -> BitLine { self.zip(other, |a, b| a & b) }
+}
+impl BitOr for &BitLine {
+    type Output = BitLine;
+    fn bitor(self, other: &BitLine) -> BitLine { self.zip(other, |a, b| a | b) }
+}
+impl BitXor for &BitLine {
+    type Output = BitLine;
+    fn bitxor(self, other: &BitLine) -> BitLine { self.zip(other, |a, b| a ^ b) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BitLine;
+
+    #[test]
+    fn bitline() {
+        let mut foo = BitLine::new();
+        assert!(!foo[0]);
+        assert_eq!(foo.first_set(), None);
+        foo.set(2, true);
+        foo.set(4, true);
+        foo.set(-70, true);
+        foo.set(130, true);
+        assert!(foo[2] && foo[4] && foo[-70] && foo[130]);
+        assert!(!foo[3] && !foo[-69] && !foo[1000]);
+        assert_eq!(foo.count_ones(), 4);
+        assert_eq!(foo.first_set(), Some(-70));
+        assert_eq!(foo.last_set(), Some(130));
+        assert_eq!(foo.iter_ones().collect::<Vec<_>>(), vec![-70, 2, 4, 130]);
+        foo.set(130, false);
+        assert_eq!(foo.last_set(), Some(4));
+
+        let moved = foo.shifted(-61);
+        assert_eq!(moved.iter_ones().collect::<Vec<_>>(), vec![-131, -59, -57]);
+        assert_ne!(moved, foo);
+        assert_eq!(moved.pattern().unwrap().1, foo.pattern().unwrap().1);
+        assert_eq!(moved.window_hash(), foo.window_hash());
+        assert_eq!(moved.shifted(61), foo);
+
+        let other = BitLine::from_initial(&[false, false, true, true]);
+        assert_eq!((&foo & &other).iter_ones().collect::<Vec<_>>(), vec![2]);
+        assert_eq!((&foo | &other).iter_ones().collect::<Vec<_>>(), vec![-70, 2, 3, 4]);
+        assert_eq!((&foo ^ &other).iter_ones().collect::<Vec<_>>(), vec![-70, 3, 4]);
+        assert_eq!(foo.and_not(&other).iter_ones().collect::<Vec<_>>(), vec![-70, 4]);
+    }
+
+    #[test]
+    fn day12_rule() {
+        let initial: Vec<bool> = "#..#.#..##......###...###".chars().map(|c| c == '#').collect();
+        let rules = ["...##", "..#..", ".#...", ".#.#.", ".#.##", ".##..", ".####",
+                     "#.#.#", "#.###", "##.#.", "##.##", "###..", "###.#", "####."];
+        let codes: Vec<u32> = rules.iter()
+            .map(|r| r.chars().fold(0, |k, c| k << 1 | (c == '#') as u32))
+            .collect();
+        let mut pots = BitLine::from_initial(&initial);
+        for _ in 0..20 {
+            pots = pots.step_rule(2, |code| codes.contains(&code));
+        }
+        assert_eq!(pots.iter_ones().sum::<i64>(), 325);
+    }
+}
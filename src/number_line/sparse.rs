@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::ops::{Index, IndexMut};
+use std::vec::Vec;
+
+const PAGE: i64 = 256;
+
+/// A `NumberLine` that stores only the fixed-size pages that have been
+/// written to, so writes far apart cost a page each rather than everything
+/// in between.
+#[derive(Clone)]
+pub struct SparseLine<T: Copy + PartialEq> {
+    pages: BTreeMap<i64, Vec<T>>,
+    default_val: T,
+}
+impl<T: Copy + PartialEq> SparseLine<T> {
+    pub fn new(default_val: T) -> Self {
+        Self {
+            pages: BTreeMap::new(),
+            default_val,
+        }
+    }
+    pub fn from_initial(initial: &[T], default_val: T) -> Self {
+        let mut line = Self::new(default_val);
+        for (idx, val) in initial.iter().enumerate() {
+            line[idx as i64] = *val;
+        }
+        line
+    }
+    /// Slots stored, default-valued or not.
+    pub fn len(&self) -> usize { self.pages.len() * PAGE as usize }
+    pub fn is_empty(&self) -> bool { self.pages.is_empty() }
+    pub fn page_count(&self) -> usize { self.pages.len() }
+    pub fn start_index(&self) -> i64 {
+        self.pages.keys().next().map_or(0, |p| p * PAGE)
+    }
+    pub fn end_index(&self) -> i64 {
+        self.pages.keys().next_back().map_or(0, |p| (p + 1) * PAGE)
+    }
+    /// Drops the pages that hold only default values.
+    pub fn trim(&mut self) {
+        let default_val = self.default_val;
+        self.pages.retain(|_, page| page.iter().any(|v| *v != default_val));
+    }
+    /// Values from the first non-default one to the last, skipping pages
+    /// that hold only default values.
+    pub fn enumerate(&self) -> impl Iterator<Item = (i64, T)> + '_ {
+        let default_val = self.default_val;
+        let first = self.pages.iter().find_map(|(p, page)|
+            page.iter().position(|v| *v != default_val).map(|i| p * PAGE + i as i64));
+        let last = self.pages.iter().rev().find_map(|(p, page)|
+            page.iter().rposition(|v| *v != default_val).map(|i| p * PAGE + i as i64));
+        let (first, last) = (first.unwrap_or(0), last.unwrap_or(-1));
+        self.pages.range(first.div_euclid(PAGE)..=last.div_euclid(PAGE))
+            .filter(move |(_, page)| page.iter().any(|v| *v != default_val))
+            .flat_map(move |(p, page)| page.iter().enumerate()
+                .map(move |(i, v)| (p * PAGE + i as i64, *v)))
+            .filter(move |(idx, _)| (first..=last).contains(idx))
+    }
+}
+impl<T: Copy + PartialEq> Index<i64> for SparseLine<T> {
+    type Output = T;
+    fn index(&self, idx: i64) -> &Self::Output {
+        match self.pages.get(&idx.div_euclid(PAGE)) {
+            Some(page) => &page[idx.rem_euclid(PAGE) as usize],
+            None => &self.default_val,
+        }
+    }
+}
+impl<T: Copy + PartialEq> IndexMut<i64> for SparseLine<T> {
+    fn index_mut(&mut self, idx: i64) -> &mut Self::Output {
+        let default_val = self.default_val;
+        let page = self.pages.entry(idx.div_euclid(PAGE))
+            .or_insert_with(|| vec![default_val; PAGE as usize]);
+        &mut page[idx.rem_euclid(PAGE) as usize]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SparseLine;
+
+    #[test]
+    fn sparse() {
+        let mut foo = SparseLine::new(0u8);
+        assert_eq!(foo[5], 0);
+        assert!(foo.is_empty());
+        assert_eq!(foo.enumerate().next(), None);
+
+        foo[-1_000_000_000_000] = 1;
+        foo[1_000_000_000_000] = 2;
+        foo[3] = 3;
+        foo[5] = 4;
+        assert_eq!(foo.page_count(), 3);
+        assert_eq!(foo[-1_000_000_000_000], 1);
+        assert_eq!(foo[1_000_000_000_000], 2);
+        assert_eq!(foo[4], 0);
+        assert_eq!(foo[-1], 0);
+        assert_eq!(foo.start_index(), -1_000_000_000_000);
+
+        let all: Vec<_> = foo.enumerate().collect();
+        assert_eq!(all.len(), 256 * 2 + 1);
+        assert_eq!(all[0], (-1_000_000_000_000, 1));
+        assert_eq!(all[all.len() - 1], (1_000_000_000_000, 2));
+        assert_eq!(all.iter().filter(|x| x.1 != 0).count(), 4);
+
+        foo[-1_000_000_000_000] = 0;
+        foo[-300] = 0;
+        assert_eq!(foo.page_count(), 4);
+        assert_eq!(foo.enumerate().next(), Some((3, 3)));
+        foo.trim();
+        assert_eq!(foo.page_count(), 2);
+        assert_eq!(foo.enumerate().next(), Some((3, 3)));
+    }
+}
@@ -0,0 +1,187 @@
+use std::cmp::max;
+use std::ops::{Index, IndexMut, Range};
+use std::vec::Vec;
+
+/// The 2D counterpart of `NumberLine`: reads outside the stored area give
+/// the default value and writes outside it grow the area, at least doubling
+/// the stored width or height so repeated growth is amortised O(1).
+#[derive(Clone)]
+pub struct NumberPlane<T: Copy + PartialEq> {
+    min_x: i64,
+    min_y: i64,
+    x_size: usize,
+    y_size: usize,
+    data: Vec<T>,
+    default_val: T,
+}
+impl<T: Copy + PartialEq> NumberPlane<T> {
+    pub fn new(default_val: T) -> Self {
+        Self {
+            min_x: 0,
+            min_y: 0,
+            x_size: 0,
+            y_size: 0,
+            data: Vec::new(),
+            default_val,
+        }
+    }
+    pub fn with_bounds(min_x: i64, min_y: i64, max_x: i64, max_y: i64, default_val: T) -> Self {
+        assert!(max_x >= min_x && max_y >= min_y);
+        let x_size = (max_x - min_x + 1) as usize;
+        let y_size = (max_y - min_y + 1) as usize;
+        Self {
+            min_x,
+            min_y,
+            x_size,
+            y_size,
+            data: vec![default_val; x_size * y_size],
+            default_val,
+        }
+    }
+    /// The stored area; everything outside it is default-valued.
+    pub fn x_bounds(&self) -> Range<i64> { self.min_x .. self.min_x + self.x_size as i64 }
+    pub fn y_bounds(&self) -> Range<i64> { self.min_y .. self.min_y + self.y_size as i64 }
+
+    pub fn get(&self, x: i64, y: i64) -> T { self[(x, y)] }
+    pub fn set(&mut self, x: i64, y: i64, val: T) { self[(x, y)] = val; }
+
+    fn offset(&self, x: i64, y: i64) -> Option<usize> {
+        (self.x_bounds().contains(&x) && self.y_bounds().contains(&y))
+            .then(|| (y - self.min_y) as usize * self.x_size + (x - self.min_x) as usize)
+    }
+    // New (start, size) for one axis so that it takes in `v`.
+    fn grow_axis(start: i64, size: usize, v: i64) -> (i64, usize) {
+        let size = size as i64;
+        if size == 0 {
+            (v, 1)
+        }
+        else if v < start {
+            let ext = max(start - v, size);
+            (start - ext, (size + ext) as usize)
+        }
+        else if v >= start + size {
+            let ext = max(v - start - size + 1, size);
+            (start, (size + ext) as usize)
+        }
+        else {
+            (start, size as usize)
+        }
+    }
+    fn grow_to(&mut self, x: i64, y: i64) {
+        let (min_x, x_size) = Self::grow_axis(self.min_x, self.x_size, x);
+        let (min_y, y_size) = Self::grow_axis(self.min_y, self.y_size, y);
+        let mut data = vec![self.default_val; x_size * y_size];
+        let dx = (self.min_x - min_x) as usize;
+        for (row, old) in self.data.chunks(self.x_size.max(1)).enumerate() {
+            let start = (row + (self.min_y - min_y) as usize) * x_size + dx;
+            data[start .. start + old.len()].copy_from_slice(old);
+        }
+        *self = Self { min_x, min_y, x_size, y_size, data, default_val: self.default_val };
+    }
+
+    /// The smallest area holding every non-default cell, or `None` if there
+    /// aren't any.
+    pub fn bounding_box(&self) -> Option<(Range<i64>, Range<i64>)> {
+        let mut cells = self.iter().filter(|(_, _, v)| *v != self.default_val);
+        let (x, y, _) = cells.next()?;
+        let (mut min_x, mut max_x, mut max_y) = (x, x, y);
+        for (x, y, _) in cells {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            max_y = y;
+        }
+        Some((min_x .. max_x + 1, y .. max_y + 1))
+    }
+    /// Every stored cell as `(x, y, value)`, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, i64, T)> + '_ {
+        self.data.iter().enumerate().map(|(i, v)| (
+            self.min_x + (i % self.x_size) as i64,
+            self.min_y + (i / self.x_size) as i64,
+            *v,
+        ))
+    }
+    /// The stored cells of row `y` as `(x, value)`.
+    pub fn row(&self, y: i64) -> impl Iterator<Item = (i64, T)> + '_ {
+        self.x_bounds().map(move |x| (x, self[(x, y)]))
+    }
+    /// The stored cells of column `x` as `(y, value)`.
+    pub fn col(&self, x: i64) -> impl Iterator<Item = (i64, T)> + '_ {
+        self.y_bounds().map(move |y| (y, self[(x, y)]))
+    }
+    /// One line per row of the given area.
+    pub fn format_area<F>(&self, xs: Range<i64>, ys: Range<i64>, formatter: F) -> String
+            where F: Fn(T) -> char {
+        let mut s = String::new();
+        for y in ys {
+            s.extend(xs.clone().map(|x| formatter(self[(x, y)])));
+            s.push('\n');
+        }
+        s
+    }
+    /// The bounding box rendered one line per row; empty if every cell is
+    /// default.
+    pub fn format<F>(&self, formatter: F) -> String
+            where F: Fn(T) -> char {
+        match self.bounding_box() {
+            Some((xs, ys)) => self.format_area(xs, ys, formatter),
+            None => String::new(),
+        }
+    }
+}
+impl<T: Copy + PartialEq> Index<(i64, i64)> for NumberPlane<T> {
+    type Output = T;
+    fn index(&self, (x, y): (i64, i64)) -> &Self::Output {
+        match self.offset(x, y) {
+            Some(i) => &self.data[i],
+            None => &self.default_val,
+        }
+    }
+}
+impl<T: Copy + PartialEq> IndexMut<(i64, i64)> for NumberPlane<T> {
+    fn index_mut(&mut self, (x, y): (i64, i64)) -> &mut Self::Output {
+        if self.offset(x, y).is_none() {
+            self.grow_to(x, y);
+        }
+        let i = self.offset(x, y).unwrap();
+        &mut self.data[i]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NumberPlane;
+
+    #[test]
+    fn numberplane() {
+        let mut foo = NumberPlane::new('.');
+        assert_eq!(foo[(3, -7)], '.');
+        assert_eq!(foo.bounding_box(), None);
+        assert_eq!(foo.format(|c| c), "");
+
+        foo[(2, 1)] = 'a';
+        foo.set(-1, 3, 'b');
+        foo[(4, -2)] = 'c';
+        assert_eq!(foo.get(2, 1), 'a');
+        assert_eq!(foo[(-1, 3)], 'b');
+        assert_eq!(foo[(4, -2)], 'c');
+        assert_eq!(foo[(0, 0)], '.');
+        assert_eq!(foo[(100, 100)], '.');
+        assert!(foo.x_bounds().contains(&-1) && foo.x_bounds().contains(&4));
+        assert_eq!(foo.bounding_box(), Some((-1..5, -2..4)));
+        assert_eq!(foo.format(|c| c), "\
+.....c
+......
+......
+...a..
+......
+b.....
+");
+        assert_eq!(foo.row(1).filter(|(_, v)| *v != '.').collect::<Vec<_>>(), vec![(2, 'a')]);
+        assert_eq!(foo.col(-1).filter(|(_, v)| *v != '.').collect::<Vec<_>>(), vec![(3, 'b')]);
+        assert_eq!(foo.format_area(1..4, 0..2, |c| c), "...\n.a.\n");
+
+        let mut bar = NumberPlane::with_bounds(0, 0, 2, 2, 0u8);
+        bar[(1, 1)] = 1;
+        assert_eq!(bar.bounding_box(), Some((1..2, 1..2)));
+    }
+}
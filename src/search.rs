@@ -0,0 +1,322 @@
+//! Best-first graph search: breadth-first, Dijkstra and A* over any node
+//! type, with the neighbours supplied by a closure.
+//!
+//! The frontier is ordered by cost (plus the heuristic, if any), then by
+//! the tie-break key, then by the order nodes were found, so nodes of equal
+//! cost are settled in key order. Each node's predecessor is the first
+//! settled node that reached it at its best cost.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+use std::vec::Vec;
+
+/// What step costs can be: `usize`, `i64` and the like, with `Default` as
+/// zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+type Neighbours<'a, N, C> = Box<dyn FnMut(&N, &mut Vec<(N, C)>) + 'a>;
+type Heuristic<'a, N, C> = Box<dyn FnMut(&N) -> C + 'a>;
+
+pub struct Search<'a, N, C, K = ()> {
+    neighbours: Neighbours<'a, N, C>,
+    heuristic: Option<Heuristic<'a, N, C>>,
+    key: Box<dyn Fn(&N) -> K + 'a>,
+}
+
+impl<'a, N: 'a> Search<'a, N, usize> {
+    /// Every step costs 1.
+    pub fn bfs<I, F>(mut neighbours: F) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        F: FnMut(&N) -> I + 'a,
+    {
+        Search::dijkstra(move |n: &N| neighbours(n).into_iter().map(|m| (m, 1)))
+    }
+}
+
+impl<'a, N: 'a, C: 'a> Search<'a, N, C> {
+    /// Each neighbour comes with the cost of the step to it.
+    pub fn dijkstra<I, F>(mut neighbours: F) -> Self
+    where
+        I: IntoIterator<Item = (N, C)>,
+        F: FnMut(&N) -> I + 'a,
+    {
+        Search {
+            neighbours: Box::new(move |n, out| out.extend(neighbours(n))),
+            heuristic: None,
+            key: Box::new(|_| ()),
+        }
+    }
+}
+
+impl<'a, N: 'a, C: 'a, K: 'a> Search<'a, N, C, K> {
+    /// Makes this A*. The heuristic must never overestimate the cost to the
+    /// nearest goal, and should be zero at the goals.
+    pub fn heuristic<H: FnMut(&N) -> C + 'a>(mut self, heuristic: H) -> Self {
+        self.heuristic = Some(Box::new(heuristic));
+        self
+    }
+
+    /// Breaks ties between nodes of equal cost by `key`, smallest first;
+    /// `|&(x, y)| (y, x)` gives reading order.
+    pub fn tiebreak<K2, F: Fn(&N) -> K2 + 'a>(self, key: F) -> Search<'a, N, C, K2> {
+        Search {
+            neighbours: self.neighbours,
+            heuristic: self.heuristic,
+            key: Box::new(key),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash, C: Cost, K: Ord> Search<'_, N, C, K> {
+    /// Searches everything reachable from `start`.
+    pub fn from(self, start: N) -> Paths<N, C> {
+        self.run(start, |_| false)
+    }
+
+    /// Searches from `start` until the nearest goals are settled. Every goal
+    /// at that cost ends up in `Paths::goals`; nodes left on the frontier
+    /// are dropped, so the paths hold only settled nodes.
+    pub fn to<G: FnMut(&N) -> bool>(self, start: N, is_goal: G) -> Paths<N, C> {
+        self.run(start, is_goal)
+    }
+
+    fn entry(&mut self, node: N, cost: C, seq: &mut usize) -> Entry<N, C, K> {
+        let priority = match &mut self.heuristic {
+            Some(h) => cost + h(&node),
+            None => cost,
+        };
+        *seq += 1;
+        Entry { priority, key: (self.key)(&node), seq: *seq, cost, node }
+    }
+
+    fn run<G: FnMut(&N) -> bool>(mut self, start: N, mut is_goal: G) -> Paths<N, C> {
+        let mut paths = Paths { dist: HashMap::new(), prev: HashMap::new(), goals: Vec::new() };
+        let mut heap = BinaryHeap::new();
+        let mut seq = 0;
+        let mut best: Option<C> = None;
+        let mut out = Vec::new();
+        paths.dist.insert(start.clone(), C::default());
+        heap.push(self.entry(start, C::default(), &mut seq));
+
+        while let Some(entry) = heap.pop() {
+            if paths.dist[&entry.node] < entry.cost {
+                continue;
+            }
+            if best.is_some_and(|b| entry.priority > b) {
+                for e in std::iter::once(entry).chain(heap.drain()) {
+                    if paths.dist.get(&e.node) == Some(&e.cost) {
+                        paths.dist.remove(&e.node);
+                        paths.prev.remove(&e.node);
+                    }
+                }
+                break;
+            }
+            if is_goal(&entry.node) {
+                best = Some(entry.priority);
+                paths.goals.push(entry.node.clone());
+            }
+            (self.neighbours)(&entry.node, &mut out);
+            for (next, step) in out.drain(..) {
+                let cost = entry.cost + step;
+                if paths.dist.get(&next).is_none_or(|d| cost < *d) {
+                    paths.dist.insert(next.clone(), cost);
+                    paths.prev.insert(next.clone(), entry.node.clone());
+                    heap.push(self.entry(next, cost, &mut seq));
+                }
+            }
+        }
+        paths
+    }
+}
+
+struct Entry<N, C, K> {
+    priority: C,
+    key: K,
+    seq: usize,
+    cost: C,
+    node: N,
+}
+
+// The priority queue depends on `Ord`.
+// Explicitly implement the trait so the queue becomes a min-heap
+// instead of a max-heap. `seq` is unique, so no two entries are equal.
+impl<N, C: Ord, K: Ord> Ord for Entry<N, C, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+            .then_with(|| other.key.cmp(&self.key))
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl<N, C: Ord, K: Ord> PartialOrd for Entry<N, C, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord, K: Ord> PartialEq for Entry<N, C, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord, K: Ord> Eq for Entry<N, C, K> {}
+
+/// What a search found: the cost to each node it reached and the way there.
+#[derive(Clone, Debug)]
+pub struct Paths<N, C> {
+    dist: HashMap<N, C>,
+    prev: HashMap<N, N>,
+    goals: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Paths<N, C> {
+    pub fn dist(&self, node: &N) -> Option<C> {
+        self.dist.get(node).copied()
+    }
+
+    pub fn dists(&self) -> &HashMap<N, C> {
+        &self.dist
+    }
+
+    /// The goals at the lowest cost, in the order they were settled.
+    pub fn goals(&self) -> &[N] {
+        &self.goals
+    }
+
+    /// The node before `node` on its path; `None` for the start.
+    pub fn prev(&self, node: &N) -> Option<&N> {
+        self.prev.get(node)
+    }
+
+    /// The path from the start to `node`, both included.
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.dist.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(p) = self.prev.get(path.last().unwrap()) {
+            path.push(p.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAZE: &str = "\
+#########
+#S..#...#
+#.#.#.#.#
+#.#...#G#
+#...#.#.#
+###.#G..#
+#########";
+
+    fn maze() -> Vec<Vec<char>> {
+        MAZE.lines().map(|l| l.chars().collect()).collect()
+    }
+
+    fn find(grid: &[Vec<char>], c: char) -> (usize, usize) {
+        grid.iter().enumerate()
+            .find_map(|(y, row)| row.iter().position(|&d| d == c).map(|x| (x, y)))
+            .unwrap()
+    }
+
+    fn open(grid: &[Vec<char>]) -> impl Fn(&(usize, usize)) -> Vec<(usize, usize)> + '_ {
+        |&(x, y)| [(x, y - 1), (x - 1, y), (x + 1, y), (x, y + 1)]
+            .into_iter()
+            .filter(|&(x, y)| grid[y][x] != '#')
+            .collect()
+    }
+
+    #[test]
+    fn bfs() {
+        let grid = maze();
+        let start = find(&grid, 'S');
+        let paths = Search::bfs(open(&grid)).from(start);
+        assert_eq!(paths.dist(&(7, 3)), Some(12));
+        assert_eq!(paths.dist(&(5, 5)), Some(8));
+        assert_eq!(paths.dist(&(0, 0)), None);
+        assert_eq!(paths.dists().len(), 24);
+
+        let path = paths.path_to(&(5, 5)).unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.first(), Some(&start));
+        assert!(path.windows(2).all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1));
+        assert_eq!(paths.prev(&start), None);
+        assert_eq!(paths.path_to(&(0, 0)), None);
+    }
+
+    #[test]
+    fn nearest() {
+        let grid = maze();
+        let start = find(&grid, 'S');
+        let is_goal = |&(x, y): &(usize, usize)| grid[y][x] == 'G';
+        let paths = Search::bfs(open(&grid)).to(start, is_goal);
+        assert_eq!(paths.goals(), &[(5, 5)]);
+        // Only what's nearer than the goal, or as near, is kept.
+        assert!(paths.dists().values().all(|&d| d <= 8));
+        assert_eq!(paths.dist(&(7, 3)), None);
+
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (y, x)).to((7, 4), is_goal);
+        assert_eq!(paths.goals(), &[(7, 3)]);
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (y, x)).to((6, 5), is_goal);
+        assert_eq!(paths.goals(), &[(5, 5)]);
+        // Both goals are 2 from here, and come out in key order.
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (y, x)).to((7, 5), is_goal);
+        assert_eq!(paths.goals(), &[(7, 3), (5, 5)]);
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (usize::MAX - y, x)).to((7, 5), is_goal);
+        assert_eq!(paths.goals(), &[(5, 5), (7, 3)]);
+
+        // (3, 4) is 5 by way of either (3, 3) or (2, 4); the predecessor
+        // kept is the first in key order.
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (y, x)).from(start);
+        assert_eq!(paths.prev(&(3, 4)), Some(&(3, 3)));
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (usize::MAX - y, x)).from(start);
+        assert_eq!(paths.prev(&(3, 4)), Some(&(2, 4)));
+        let paths = Search::bfs(open(&grid)).tiebreak(|&(x, y)| (y, x)).from((3, 3));
+        assert_eq!(paths.prev(&(3, 3)), None);
+        assert_eq!(paths.path_to(&(5, 1)).unwrap(), vec![(3, 3), (4, 3), (5, 3), (5, 2), (5, 1)]);
+    }
+
+    #[test]
+    fn weighted() {
+        // Going through water costs 5 a step.
+        let grid: Vec<Vec<char>> = "\
+S~~~~G
+.~##~.
+......".lines().map(|l| l.chars().collect()).collect();
+        let (w, h) = (grid[0].len() as i64, grid.len() as i64);
+        let at = |x: i64, y: i64| grid[y as usize][x as usize];
+        let neighbours = |&(x, y): &(i64, i64)| -> Vec<((i64, i64), i64)> {
+            [(x, y - 1), (x - 1, y), (x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| x >= 0 && y >= 0 && x < w && y < h && at(x, y) != '#')
+                .map(|(x, y)| ((x, y), if at(x, y) == '~' { 5 } else { 1 }))
+                .collect()
+        };
+        let goal = (5, 0);
+        let paths = Search::dijkstra(neighbours).to((0, 0), |&p| p == goal);
+        assert_eq!(paths.dist(&goal), Some(9));
+        assert_eq!(paths.path_to(&goal).unwrap().len(), 10);
+
+        let astar = Search::dijkstra(neighbours)
+            .heuristic(|&(x, y)| (goal.0 - x).abs() + (goal.1 - y).abs())
+            .to((0, 0), |&p| p == goal);
+        assert_eq!(astar.dist(&goal), Some(9));
+        assert_eq!(astar.goals(), &[goal]);
+        assert!(astar.dists().len() <= paths.dists().len());
+
+        let all = Search::dijkstra(neighbours).from((0, 0));
+        assert_eq!(all.dist(&(2, 0)), Some(10));
+        assert_eq!(all.dist(&(4, 1)), Some(11));
+    }
+}
@@ -1,12 +1,22 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::str::FromStr;
 use std::vec::Vec;
 use regex::Regex;
 use lazy_static::lazy_static;
 
-const NREGS: usize = 6;
+pub mod compile;
+pub mod diff;
+pub mod equiv;
+pub mod spec;
+pub mod superopt;
+pub mod taint;
+pub mod validate;
+pub use validate::{validate, Diagnostic, Severity};
 
-#[derive(Clone, Copy, Debug)]
+pub const NREGS: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Instruction {
     pub opcode: &'static str,
     pub a: usize,
@@ -22,11 +32,11 @@ impl Instruction {
         }
         if let Some(caps) = RE_INST.captures(s) {
             let o = caps.get(1).unwrap().as_str();
-            let a = caps.get(2).unwrap().as_str().parse::<usize>().unwrap();
-            let b = caps.get(3).unwrap().as_str().parse::<usize>().unwrap();
-            let c = caps.get(4).unwrap().as_str().parse::<usize>().unwrap();
+            let a = caps.get(2).unwrap().as_str().parse::<usize>().ok()?;
+            let b = caps.get(3).unwrap().as_str().parse::<usize>().ok()?;
+            let c = caps.get(4).unwrap().as_str().parse::<usize>().ok()?;
             Some(Self {
-                opcode: OPERATIONS.get_key_value(o).unwrap().0,
+                opcode: OPERATIONS.get_key_value(o)?.0,
                 a,
                 b,
                 c,
@@ -37,31 +47,89 @@ impl Instruction {
         }
     }
 }
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.opcode, self.a, self.b, self.c)
+    }
+}
 
+/// Directives in a program file. Besides `#ip`, these let a single file
+/// describe a complete run:
+///
+/// ```text
+/// #ip 3
+/// #reg 0 1          initial value of a register
+/// #label start      names the address of the next instruction
+/// #entry start      begin execution at a label instead of address 0
+/// #fuel 1000000     stop with RunResult::OutOfFuel after this many steps
+/// #expect 0 1234    expected value of a register once the program halts
+/// #break 0          break whenever an instruction reads this register
+/// #expect_break 28  expected address of the next breakpoint hit
+/// ```
+#[derive(Clone, Debug)]
 pub enum Meta {
     MapIp(usize),
+    SetReg(usize, usize),
+    Label(String),
+    Entry(String),
+    Fuel(usize),
+    Expect(usize, usize),
+    Break(usize),
+    ExpectBreak(usize),
 }
 impl Meta {
     fn from_str(s: &str) -> Option<Self> {
         lazy_static! {
-            static ref RE_IP: Regex = Regex::new(
-                r"^#ip (\d+)",
+            static ref RE_META: Regex = Regex::new(
+                r"^#(\w+) +(\w+)(?: +(\d+))?\s*$",
             ).unwrap();
         }
-        if let Some(caps) = RE_IP.captures(s) {
-            let arg = caps.get(1).unwrap().as_str().parse::<usize>().unwrap();
-            Some(Meta::MapIp(arg))
+        let caps = RE_META.captures(s)?;
+        let arg = caps.get(2).unwrap().as_str();
+        let num = || arg.parse::<usize>().ok();
+        let num2 = || caps.get(3).and_then(|m| m.as_str().parse::<usize>().ok());
+        match (caps.get(1).unwrap().as_str(), caps.get(3)) {
+            ("ip", None) => Some(Meta::MapIp(num()?)),
+            ("reg", Some(_)) => Some(Meta::SetReg(num()?, num2()?)),
+            ("label", None) => Some(Meta::Label(arg.to_owned())),
+            ("entry", None) => Some(Meta::Entry(arg.to_owned())),
+            ("fuel", None) => Some(Meta::Fuel(num()?)),
+            ("expect", Some(_)) => Some(Meta::Expect(num()?, num2()?)),
+            ("break", None) => Some(Meta::Break(num()?)),
+            ("expect_break", None) => Some(Meta::ExpectBreak(num()?)),
+            _ => None,
         }
-        else {
-            None
+    }
+}
+
+impl fmt::Display for Meta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Meta::MapIp(reg) => write!(f, "#ip {reg}"),
+            Meta::SetReg(reg, val) => write!(f, "#reg {reg} {val}"),
+            Meta::Label(name) => write!(f, "#label {name}"),
+            Meta::Entry(name) => write!(f, "#entry {name}"),
+            Meta::Fuel(val) => write!(f, "#fuel {val}"),
+            Meta::Expect(reg, val) => write!(f, "#expect {reg} {val}"),
+            Meta::Break(reg) => write!(f, "#break {reg}"),
+            Meta::ExpectBreak(addr) => write!(f, "#expect_break {addr}"),
         }
     }
 }
 
+#[derive(Clone, Debug)]
 pub enum ProgramItem {
     Instr(Instruction),
     Meta(Meta),
 }
+impl fmt::Display for ProgramItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProgramItem::Instr(inst) => inst.fmt(f),
+            ProgramItem::Meta(meta) => meta.fmt(f),
+        }
+    }
+}
 impl FromStr for ProgramItem {
     type Err = &'static str;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -81,15 +149,55 @@ pub enum RunResult {
     Ok,
     Halt,
     Break(Instruction),
+    OutOfFuel,
     Err(&'static str),
 }
 
-pub struct VM {
+/// A register whose final value didn't match its `#expect` directive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegMismatch {
+    pub reg: usize,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+/// Hooks for watching a `VM` as it runs. Every method has an empty default,
+/// so an observer only implements the events it cares about. `ip` is always
+/// the address of the instruction concerned, not the register it's bound to.
+pub trait Observer {
+    /// Called before each instruction that isn't being resumed from a break;
+    /// returning true stops the VM with `RunResult::Break`.
+    fn break_before(&mut self, _ip: usize, _inst: &Instruction, _regs: &[usize; NREGS]) -> bool {
+        false
+    }
+    fn before_instruction(&mut self, _ip: usize, _inst: &Instruction, _regs: &[usize; NREGS]) {}
+    fn after_instruction(&mut self, _ip: usize, _inst: &Instruction, _regs: &[usize; NREGS]) {}
+    fn register_write(&mut self, _reg: usize, _old: usize, _new: usize) {}
+    /// Called when an instruction writes the ip register so that the next
+    /// address isn't `from + 1`; `to` is the address that will execute next.
+    fn jump(&mut self, _from: usize, _to: usize) {}
+    /// Called once when the VM halts, not again for each later `step`.
+    fn halt(&mut self, _ip: usize, _regs: &[usize; NREGS]) {}
+}
+
+/// The observer used by a plain `VM`; every hook is a no-op and compiles away.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoObserver;
+impl Observer for NoObserver {}
+
+pub struct VM<O: Observer = NoObserver> {
     pub r: [usize; NREGS],
     pub ip: usize,
     pub prog: Vec<Instruction>,
+    /// Steps left before `step` returns `RunResult::OutOfFuel`; `None` means unlimited.
+    pub fuel: Option<usize>,
+    pub expected: Vec<(usize, usize)>,
+    pub expected_breaks: Vec<usize>,
+    labels: HashMap<String, usize>,
     break_on_access: Option<usize>,
     is_at_breakpoint: bool,
+    halted: bool,
+    observer: O,
 }
 impl Default for VM {
     fn default() -> Self {
@@ -99,25 +207,91 @@ impl Default for VM {
 
 impl VM {
     pub fn new() -> Self {
+        Self::with_observer(NoObserver)
+    }
+}
+
+impl<O: Observer> VM<O> {
+    pub fn with_observer(observer: O) -> Self {
         VM {
             r: [0; NREGS],
             ip: 0,
             prog: Vec::new(),
+            fuel: None,
+            expected: Vec::new(),
+            expected_breaks: Vec::new(),
+            labels: HashMap::new(),
             break_on_access: None,
             is_at_breakpoint: false,
+            halted: false,
+            observer,
         }
     }
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+    pub fn observer_mut(&mut self) -> &mut O {
+        &mut self.observer
+    }
+    pub fn into_observer(self) -> O {
+        self.observer
+    }
+    /// Loads a program as-is and applies its directives, starting from
+    /// zeroed registers and none of the previous program's settings; see
+    /// `validate` for checking it first. Panics if `#ip` or `#reg` names a
+    /// register out of range, if a `#label` is defined twice, or if
+    /// `#entry` names an unknown label.
     pub fn load(&mut self, program: &[ProgramItem]) {
+        self.r = [0; NREGS];
+        self.ip = 0;
         self.prog.clear();
+        self.fuel = None;
+        self.labels.clear();
+        self.expected.clear();
+        self.expected_breaks.clear();
+        self.break_on_access = None;
+        self.is_at_breakpoint = false;
+        self.halted = false;
+        let mut entry = None;
         for pi in program {
             match pi {
                 ProgramItem::Instr(inst) => self.prog.push(*inst),
                 ProgramItem::Meta(meta) =>
                     match meta {
-                        Meta::MapIp(val) => self.ip = *val,
+                        Meta::MapIp(val) => {
+                            assert!(*val < NREGS, "#ip register {val} out of range");
+                            self.ip = *val;
+                        },
+                        Meta::SetReg(reg, val) => {
+                            assert!(*reg < NREGS, "#reg register {reg} out of range");
+                            self.r[*reg] = *val;
+                        },
+                        Meta::Label(name) => {
+                            let prev = self.labels.insert(name.clone(), self.prog.len());
+                            assert!(prev.is_none(), "label {name} defined twice");
+                        },
+                        Meta::Entry(name) => entry = Some(name),
+                        Meta::Fuel(val) => self.fuel = Some(*val),
+                        Meta::Expect(reg, val) => self.expected.push((*reg, *val)),
+                        Meta::Break(reg) => self.set_breakpoint(*reg),
+                        Meta::ExpectBreak(addr) => self.expected_breaks.push(*addr),
                     }
             }
         }
+        if let Some(name) = entry {
+            self.r[self.ip] = self.label(name)
+                .unwrap_or_else(|| panic!("entry label {name} not defined"));
+        }
+    }
+    pub fn label(&self, name: &str) -> Option<usize> {
+        self.labels.get(name).copied()
+    }
+    /// Compares the registers against the program's `#expect` directives.
+    pub fn check_expected(&self) -> Vec<RegMismatch> {
+        self.expected.iter()
+            .filter(|(reg, val)| self.r[*reg] != *val)
+            .map(|(reg, val)| RegMismatch { reg: *reg, expected: *val, actual: self.r[*reg] })
+            .collect()
     }
     pub fn set_breakpoint(&mut self, register: usize) {
         self.break_on_access = Some(register);
@@ -136,31 +310,54 @@ impl VM {
         // } else {
             let a = if op.a_immed { inst.a } else { self.r[inst.a] };
             let b = if op.b_immed { inst.b } else { self.r[inst.b] };
+            let old = self.r[inst.c];
             self.r[inst.c] = (op.op)(a, b);
+            self.observer.register_write(inst.c, old, self.r[inst.c]);
         //    Ok(())
         //}
     }
     pub fn step(&mut self) -> RunResult {
-        if self.r[self.ip] >= self.prog.len() {
+        let addr = self.r[self.ip];
+        if addr >= self.prog.len() {
+            if !self.halted {
+                self.halted = true;
+                self.observer.halt(addr, &self.r);
+            }
             return RunResult::Halt;
         }
-        let inst = self.prog[self.r[self.ip]];
-        if let Some(brk) = self.break_on_access {
-            if self.is_at_breakpoint {
-                self.is_at_breakpoint = false;
-            }
-            else {
+        self.halted = false;
+        if self.fuel == Some(0) {
+            return RunResult::OutOfFuel;
+        }
+        let inst = self.prog[addr];
+        if self.is_at_breakpoint {
+            self.is_at_breakpoint = false;
+        }
+        else {
+            if let Some(brk) = self.break_on_access {
                 let op = &OPERATIONS[&inst.opcode];
                 if !op.a_immed && inst.a == brk || !op.b_immed && inst.b == brk {
                     self.is_at_breakpoint = true;
                     return RunResult::Break(inst);
                 }
             }
+            if self.observer.break_before(addr, &inst, &self.r) {
+                self.is_at_breakpoint = true;
+                return RunResult::Break(inst);
+            }
         }
+        if let Some(fuel) = self.fuel.as_mut() {
+            *fuel -= 1;
+        }
+        self.observer.before_instruction(addr, &inst, &self.r);
         //match self.exec(&inst) {
         //    Ok(()) => {
         self.exec(&inst);
+                self.observer.after_instruction(addr, &inst, &self.r);
                 self.r[self.ip] += 1;
+                if inst.c == self.ip && self.r[self.ip] != addr + 1 {
+                    self.observer.jump(addr, self.r[self.ip]);
+                }
                 RunResult::Ok
         //    },
         //    Err(e) => RunResult::Err(e),
@@ -210,3 +407,91 @@ lazy_static! {
             ("eqrr", Op{a_immed:false, b_immed:false, op:|a, b| if a == b { 1 } else { 0 }}),
         ]);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder {
+        executed: Vec<usize>,
+        writes: usize,
+        jumps: Vec<(usize, usize)>,
+        halts: Vec<usize>,
+    }
+    impl Observer for Recorder {
+        fn before_instruction(&mut self, ip: usize, _inst: &Instruction, _regs: &[usize; NREGS]) {
+            self.executed.push(ip);
+        }
+        fn register_write(&mut self, _reg: usize, _old: usize, _new: usize) {
+            self.writes += 1;
+        }
+        fn jump(&mut self, from: usize, to: usize) {
+            self.jumps.push((from, to));
+        }
+        fn halt(&mut self, ip: usize, _regs: &[usize; NREGS]) {
+            self.halts.push(ip);
+        }
+    }
+
+    fn program(src: &str) -> Vec<ProgramItem> {
+        src.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn observer() {
+        let prog = program("#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\nsetr 1 0 0\nseti 8 0 4\nseti 9 0 5");
+        let mut vm = VM::with_observer(Recorder::default());
+        vm.load(&prog);
+        assert!(matches!(vm.run(), RunResult::Halt));
+        assert!(matches!(vm.step(), RunResult::Halt));
+        assert_eq!(vm.r, [7, 5, 6, 0, 0, 9]);
+        let rec = vm.into_observer();
+        assert_eq!(rec.executed, vec![0, 1, 2, 4, 6]);
+        assert_eq!(rec.writes, 5);
+        assert_eq!(rec.jumps, vec![(2, 4), (4, 6)]);
+        assert_eq!(rec.halts, vec![7]);
+
+        // Writing the ip without changing where execution goes isn't a jump.
+        let mut vm = VM::with_observer(Recorder::default());
+        vm.load(&program("#ip 1\nsetr 1 0 1\naddi 0 3 0"));
+        assert!(matches!(vm.run(), RunResult::Halt));
+        let rec = vm.into_observer();
+        assert_eq!(rec.executed, vec![0, 1]);
+        assert!(rec.jumps.is_empty());
+        assert_eq!(rec.halts, vec![2]);
+    }
+
+    #[test]
+    fn directives() {
+        let prog = program("#ip 2\n#reg 0 1\n#fuel 10\n#expect 0 4\n#expect 1 3\naddi 0 1 0\n#label main\naddi 1 1 1\nseti 0 0 2\n#entry main");
+        let mut vm = VM::new();
+        vm.load(&prog);
+        assert_eq!(vm.r, [1, 0, 1, 0, 0, 0]);
+        assert!(matches!(vm.run(), RunResult::OutOfFuel));
+        assert_eq!(vm.r, [1, 5, 1, 0, 0, 0]);
+        assert_eq!(vm.check_expected(), vec![
+            RegMismatch { reg: 0, expected: 4, actual: 1 },
+            RegMismatch { reg: 1, expected: 3, actual: 5 },
+        ]);
+
+        // nothing carries over into the next program
+        vm.load(&program("addi 1 2 1"));
+        assert_eq!((vm.r, vm.ip, vm.fuel), ([0; NREGS], 0, None));
+        assert!(vm.check_expected().is_empty());
+        assert!(matches!(vm.run(), RunResult::Halt));
+        assert_eq!(vm.r, [1, 2, 0, 0, 0, 0]);
+
+        assert!("#reg 1 99999999999999999999999".parse::<ProgramItem>().is_err());
+        assert!("seti 99999999999999999999999 0 1".parse::<ProgramItem>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "label top defined twice")]
+    fn duplicate_label() {
+        VM::new().load(&program("#label top
+seti 0 0 0
+#label top
+addi 1 1 1"));
+    }
+}
@@ -0,0 +1,538 @@
+//! A compiler for a tiny language that targets elfcode.
+//!
+//! ```text
+//! var n: r0;          // pin a variable to a register
+//! var sum;            // or let the compiler pick one
+//! var i = 1;
+//! while (i <= n) {
+//!     sum = sum + i;
+//!     i = i + 1;
+//! }
+//! if (sum > 100) { n = 1; } else { n = 0; }
+//! ```
+//!
+//! Expressions have `*`, `+`, `&`, `|` (binding in that order, tightest
+//! first) and a single comparison `== != < <= > >=` on top. There's no
+//! subtraction or division because elfcode has none. Conditions are true
+//! when nonzero. Registers not holding variables or the ip are used as
+//! temporaries, so deeply nested expressions can run out of them.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::vec::Vec;
+use super::{Instruction, Meta, ProgramItem, NREGS};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileError {
+    pub line: usize,
+    pub message: String,
+}
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+fn err<T>(line: usize, message: impl Into<String>) -> Result<T, CompileError> {
+    Err(CompileError { line, message: message.into() })
+}
+
+/// Compiles `src` into a program with its ip bound to `ip_reg`.
+pub fn compile(src: &str, ip_reg: usize) -> Result<Vec<ProgramItem>, CompileError> {
+    if ip_reg >= NREGS {
+        return err(0, format!("ip register {ip_reg} is out of range (0..{NREGS})"));
+    }
+    let tokens = tokenize(src)?;
+    let mut parser = Parser { tokens, pos: 0, vars: HashMap::new(), busy: [false; NREGS] };
+    parser.busy[ip_reg] = true;
+    let mut stmts = Vec::new();
+    while parser.peek().is_some() {
+        stmts.extend(parser.stmt()?);
+    }
+
+    let mut gen = Gen { ip: ip_reg, busy: parser.busy, code: Vec::new() };
+    gen.block(&stmts)?;
+    Ok([ProgramItem::Meta(Meta::MapIp(ip_reg))].into_iter()
+        .chain(gen.code.into_iter().map(ProgramItem::Instr))
+        .collect())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Num(usize),
+    Ident(String),
+    Sym(&'static str),
+}
+
+const SYMBOLS: [&str; 17] = [
+    "==", "!=", "<=", ">=", "<", ">", "=", "+", "*", "&", "|",
+    "(", ")", "{", "}", ";", ":",
+];
+
+fn tokenize(src: &str) -> Result<Vec<(Tok, usize)>, CompileError> {
+    let mut tokens = Vec::new();
+    for (idx, text) in src.lines().enumerate() {
+        let line = idx + 1;
+        let text = text.split("//").next().unwrap();
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let c = rest.chars().next().unwrap();
+            let len = if c.is_ascii_digit() {
+                let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+                match rest[..len].parse() {
+                    Ok(n) => tokens.push((Tok::Num(n), line)),
+                    Err(_) => return err(line, format!("number {} is too large", &rest[..len])),
+                }
+                len
+            }
+            else if c.is_ascii_alphabetic() || c == '_' {
+                let len = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+                tokens.push((Tok::Ident(rest[..len].to_owned()), line));
+                len
+            }
+            else if let Some(sym) = SYMBOLS.iter().find(|s| rest.starts_with(*s)) {
+                tokens.push((Tok::Sym(sym), line));
+                sym.len()
+            }
+            else if c == '-' || c == '/' {
+                return err(line, format!("elfcode has no `{c}` operator"));
+            }
+            else {
+                return err(line, format!("unexpected character `{c}`"));
+            };
+            rest = rest[len..].trim_start();
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinOp { Add, Mul, And, Or, Eq, Ne, Lt, Le, Gt, Ge }
+
+impl BinOp {
+    fn is_comparison(self) -> bool {
+        !matches!(self, BinOp::Add | BinOp::Mul | BinOp::And | BinOp::Or)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Num(usize),
+    Var(usize),
+    Bin(BinOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn reads(&self, reg: usize) -> bool {
+        match self {
+            Expr::Num(_) => false,
+            Expr::Var(r) => *r == reg,
+            Expr::Bin(_, l, r) => l.reads(reg) || r.reads(reg),
+        }
+    }
+    fn is_leaf(&self) -> bool {
+        !matches!(self, Expr::Bin(..))
+    }
+}
+
+enum Stmt {
+    Assign(usize, usize, Expr),
+    If(usize, Expr, Vec<Stmt>, Vec<Stmt>),
+    While(usize, Expr, Vec<Stmt>),
+}
+
+struct Parser {
+    tokens: Vec<(Tok, usize)>,
+    pos: usize,
+    vars: HashMap<String, usize>,
+    busy: [bool; NREGS],
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.0)
+    }
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or(self.tokens.last()).map_or(0, |t| t.1)
+    }
+    fn next(&mut self) -> Result<Tok, CompileError> {
+        match self.tokens.get(self.pos) {
+            Some((tok, _)) => {
+                self.pos += 1;
+                Ok(tok.clone())
+            },
+            None => err(self.line(), "unexpected end of input"),
+        }
+    }
+    fn accept(&mut self, sym: &str) -> bool {
+        if matches!(self.peek(), Some(Tok::Sym(s)) if *s == sym) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn expect(&mut self, sym: &str) -> Result<(), CompileError> {
+        if self.accept(sym) { Ok(()) } else { err(self.line(), format!("expected `{sym}`")) }
+    }
+    fn ident(&mut self) -> Result<String, CompileError> {
+        let line = self.line();
+        match self.next()? {
+            Tok::Ident(name) => Ok(name),
+            _ => err(line, "expected a name"),
+        }
+    }
+
+    fn stmt(&mut self) -> Result<Vec<Stmt>, CompileError> {
+        let line = self.line();
+        match self.peek() {
+            Some(Tok::Ident(kw)) if kw == "var" => {
+                self.pos += 1;
+                let name = self.ident()?;
+                if self.vars.contains_key(&name) {
+                    return err(line, format!("variable {name} is already declared"));
+                }
+                let reg = if self.accept(":") {
+                    let reg = self.ident()?;
+                    match reg.strip_prefix('r').and_then(|n| n.parse::<usize>().ok()) {
+                        Some(r) if r < NREGS && !self.busy[r] => r,
+                        Some(r) if r < NREGS => return err(line, format!("register r{r} is already in use")),
+                        _ => return err(line, format!("{reg} is not a register")),
+                    }
+                } else {
+                    match (0..NREGS).find(|r| !self.busy[*r]) {
+                        Some(r) => r,
+                        None => return err(line, "out of registers for variables"),
+                    }
+                };
+                self.busy[reg] = true;
+                self.vars.insert(name, reg);
+                let init = if self.accept("=") {
+                    vec![Stmt::Assign(line, reg, self.expr()?)]
+                } else {
+                    Vec::new()
+                };
+                self.expect(";")?;
+                Ok(init)
+            },
+            Some(Tok::Ident(kw)) if kw == "if" => {
+                self.pos += 1;
+                let cond = self.cond()?;
+                let then = self.block()?;
+                let els = if matches!(self.peek(), Some(Tok::Ident(kw)) if kw == "else") {
+                    self.pos += 1;
+                    if matches!(self.peek(), Some(Tok::Ident(kw)) if kw == "if") {
+                        self.stmt()?
+                    } else {
+                        self.block()?
+                    }
+                } else {
+                    Vec::new()
+                };
+                Ok(vec![Stmt::If(line, cond, then, els)])
+            },
+            Some(Tok::Ident(kw)) if kw == "while" => {
+                self.pos += 1;
+                let cond = self.cond()?;
+                let body = self.block()?;
+                Ok(vec![Stmt::While(line, cond, body)])
+            },
+            _ => {
+                let name = self.ident()?;
+                let reg = self.var(&name, line)?;
+                self.expect("=")?;
+                let e = self.expr()?;
+                self.expect(";")?;
+                Ok(vec![Stmt::Assign(line, reg, e)])
+            },
+        }
+    }
+    fn cond(&mut self) -> Result<Expr, CompileError> {
+        self.expect("(")?;
+        let e = self.expr()?;
+        self.expect(")")?;
+        Ok(e)
+    }
+    fn block(&mut self) -> Result<Vec<Stmt>, CompileError> {
+        self.expect("{")?;
+        let mut stmts = Vec::new();
+        while !self.accept("}") {
+            if self.peek().is_none() {
+                return err(self.line(), "expected `}`");
+            }
+            stmts.extend(self.stmt()?);
+        }
+        Ok(stmts)
+    }
+    fn var(&self, name: &str, line: usize) -> Result<usize, CompileError> {
+        match self.vars.get(name) {
+            Some(r) => Ok(*r),
+            None => err(line, format!("undeclared variable {name}")),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, CompileError> {
+        let l = self.binary(0)?;
+        for (sym, op) in [("==", BinOp::Eq), ("!=", BinOp::Ne), ("<=", BinOp::Le),
+                          (">=", BinOp::Ge), ("<", BinOp::Lt), (">", BinOp::Gt)] {
+            if self.accept(sym) {
+                let r = self.binary(0)?;
+                return Ok(Expr::Bin(op, Box::new(l), Box::new(r)));
+            }
+        }
+        Ok(l)
+    }
+    // precedence levels from loosest to tightest
+    fn binary(&mut self, level: usize) -> Result<Expr, CompileError> {
+        const LEVELS: [(&str, BinOp); 4] =
+            [("|", BinOp::Or), ("&", BinOp::And), ("+", BinOp::Add), ("*", BinOp::Mul)];
+        if level == LEVELS.len() {
+            return self.primary();
+        }
+        let (sym, op) = LEVELS[level];
+        let mut e = self.binary(level + 1)?;
+        while self.accept(sym) {
+            e = Expr::Bin(op, Box::new(e), Box::new(self.binary(level + 1)?));
+        }
+        Ok(e)
+    }
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        let line = self.line();
+        match self.next()? {
+            Tok::Num(n) => Ok(Expr::Num(n)),
+            Tok::Ident(name) => Ok(Expr::Var(self.var(&name, line)?)),
+            Tok::Sym("(") => {
+                let e = self.expr()?;
+                self.expect(")")?;
+                Ok(e)
+            },
+            _ => err(line, "expected an expression"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    Reg(usize),
+    Imm(usize),
+}
+
+struct Gen {
+    ip: usize,
+    busy: [bool; NREGS],
+    code: Vec<Instruction>,
+}
+
+impl Gen {
+    fn emit(&mut self, opcode: &'static str, a: usize, b: usize, c: usize) -> usize {
+        self.code.push(Instruction { opcode, a, b, c });
+        self.code.len() - 1
+    }
+    fn temp(&mut self, line: usize) -> Result<usize, CompileError> {
+        match (0..NREGS).find(|r| !self.busy[*r]) {
+            Some(r) => {
+                self.busy[r] = true;
+                Ok(r)
+            },
+            None => err(line, "expression needs more registers than are free"),
+        }
+    }
+    fn free(&mut self, reg: usize) {
+        self.busy[reg] = false;
+    }
+    // Emits a jump to an address that isn't known yet; see `patch`.
+    fn jump(&mut self) -> usize {
+        self.emit("seti", 0, 0, self.ip)
+    }
+    fn patch(&mut self, jump: usize, target: usize) {
+        // the ip is incremented after the seti, so aim one short
+        self.code[jump].a = target - 1;
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) -> Result<(), CompileError> {
+        stmts.iter().try_for_each(|s| self.stmt(s))
+    }
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), CompileError> {
+        match stmt {
+            Stmt::Assign(line, reg, e) => {
+                // computing straight into the variable would clobber it
+                // before a later part of the expression reads it
+                if !e.is_leaf() && e.reads(*reg) && !matches!(e, Expr::Bin(_, l, r) if l.is_leaf() && r.is_leaf()) {
+                    let t = self.temp(*line)?;
+                    self.expr(e, t, *line)?;
+                    self.emit("setr", t, 0, *reg);
+                    self.free(t);
+                } else {
+                    self.expr(e, *reg, *line)?;
+                }
+            },
+            Stmt::If(line, cond, then, els) => {
+                let skip_then = self.branch(cond, *line)?;
+                self.block(then)?;
+                if els.is_empty() {
+                    self.patch(skip_then, self.code.len());
+                } else {
+                    let skip_else = self.jump();
+                    self.patch(skip_then, self.code.len());
+                    self.block(els)?;
+                    self.patch(skip_else, self.code.len());
+                }
+            },
+            Stmt::While(line, cond, body) => {
+                if self.code.is_empty() {
+                    // a backward jump can't land on address 0, since seti
+                    // would have to target -1
+                    self.emit("addi", self.ip, 0, self.ip);
+                }
+                let top = self.code.len();
+                let exit = self.branch(cond, *line)?;
+                self.block(body)?;
+                let back = self.jump();
+                self.patch(back, top);
+                self.patch(exit, self.code.len());
+            },
+        }
+        Ok(())
+    }
+    // Evaluates a condition and skips the following jump if it holds;
+    // returns that jump, which the caller patches to the false branch.
+    fn branch(&mut self, cond: &Expr, line: usize) -> Result<usize, CompileError> {
+        let t = self.temp(line)?;
+        self.expr(cond, t, line)?;
+        if !matches!(cond, Expr::Bin(op, ..) if op.is_comparison()) {
+            self.emit("gtri", t, 0, t);
+        }
+        self.emit("addr", t, self.ip, self.ip);
+        self.free(t);
+        Ok(self.jump())
+    }
+
+    fn expr(&mut self, e: &Expr, dest: usize, line: usize) -> Result<(), CompileError> {
+        match e {
+            Expr::Num(n) => { self.emit("seti", *n, 0, dest); },
+            Expr::Var(r) => if *r != dest { self.emit("setr", *r, 0, dest); },
+            Expr::Bin(op, l, r) => {
+                let a = match &**l {
+                    Expr::Num(n) => Operand::Imm(*n),
+                    Expr::Var(v) => Operand::Reg(*v),
+                    _ => {
+                        self.expr(l, dest, line)?;
+                        Operand::Reg(dest)
+                    },
+                };
+                let (b, temp) = match &**r {
+                    Expr::Num(n) => (Operand::Imm(*n), None),
+                    Expr::Var(v) => (Operand::Reg(*v), None),
+                    _ => {
+                        let t = self.temp(line)?;
+                        self.expr(r, t, line)?;
+                        (Operand::Reg(t), Some(t))
+                    },
+                };
+                self.binop(*op, a, b, dest, line)?;
+                if let Some(t) = temp {
+                    self.free(t);
+                }
+            },
+        }
+        Ok(())
+    }
+    fn binop(&mut self, op: BinOp, a: Operand, b: Operand, dest: usize, line: usize) -> Result<(), CompileError> {
+        use Operand::{Imm, Reg};
+        let (rr, ri) = match op {
+            BinOp::Add => ("addr", "addi"),
+            BinOp::Mul => ("mulr", "muli"),
+            BinOp::And => ("banr", "bani"),
+            BinOp::Or => ("borr", "bori"),
+            BinOp::Eq => ("eqrr", "eqri"),
+            BinOp::Gt => ("gtrr", "gtri"),
+            BinOp::Lt => return self.binop(BinOp::Gt, b, a, dest, line),
+            BinOp::Ne | BinOp::Le | BinOp::Ge => {
+                let inverse = match op {
+                    BinOp::Ne => BinOp::Eq,
+                    BinOp::Le => BinOp::Gt,
+                    _ => BinOp::Lt,
+                };
+                self.binop(inverse, a, b, dest, line)?;
+                self.emit("eqri", dest, 0, dest);
+                return Ok(());
+            },
+        };
+        match (a, b) {
+            (Reg(x), Reg(y)) => { self.emit(rr, x, y, dest); },
+            (Reg(x), Imm(y)) => { self.emit(ri, x, y, dest); },
+            (Imm(x), Reg(y)) => match op {
+                BinOp::Gt => { self.emit("gtir", x, y, dest); },
+                BinOp::Eq => { self.emit("eqir", x, y, dest); },
+                _ => { self.emit(ri, y, x, dest); },
+            },
+            (Imm(x), Imm(y)) => {
+                let val = match op {
+                    BinOp::Add => x.checked_add(y),
+                    BinOp::Mul => x.checked_mul(y),
+                    BinOp::And => Some(x & y),
+                    BinOp::Or => Some(x | y),
+                    BinOp::Eq => Some((x == y) as usize),
+                    _ => Some((x > y) as usize),
+                };
+                match val {
+                    Some(v) => { self.emit("seti", v, 0, dest); },
+                    None => return err(line, "constant expression overflows"),
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vm::{validate, RunResult, Severity, VM};
+
+    fn run(src: &str, r0: usize) -> [usize; NREGS] {
+        let prog = compile(src, 5).unwrap();
+        assert!(validate(&prog).iter().all(|d| d.severity != Severity::Error));
+        let mut vm = VM::new();
+        vm.load(&prog);
+        vm.r[0] = r0;
+        vm.fuel = Some(100_000);
+        assert!(matches!(vm.run(), RunResult::Halt));
+        vm.r
+    }
+
+    #[test]
+    fn compile_and_run() {
+        let src = "
+            var n: r0;
+            var sum;
+            var i = 1;
+            while (i <= n) {
+                sum = sum + i * i;
+                i = i + 1;
+            }
+            // classify the sum
+            if ((sum & 1) == 1) { n = 1; } else if (sum > 1000) { n = 2; } else { n = 3; }
+        ";
+        assert_eq!(run(src, 10)[..3], [1, 385, 11]);
+        assert_eq!(run(src, 3)[..3], [3, 14, 4]);
+        assert_eq!(run(src, 20)[..3], [2, 2870, 21]);
+
+        // a loop at the very start, and an assignment that reads its target
+        // in a nested expression
+        let src = "var x: r0; var y: r1; while (x != 0 & 0) { } y = (y + 2) * (y + 3) + y;";
+        assert_eq!(run(src, 0)[1], 6);
+    }
+
+    #[test]
+    fn compile_errors() {
+        assert_eq!(compile("var x;\nx = x - 1;", 5).unwrap_err(),
+            CompileError { line: 2, message: "elfcode has no `-` operator".into() });
+        assert_eq!(compile("var x;\ny = 1;", 5).unwrap_err(),
+            CompileError { line: 2, message: "undeclared variable y".into() });
+        assert_eq!(compile("var x: r5;", 5).unwrap_err(),
+            CompileError { line: 1, message: "register r5 is already in use".into() });
+        assert_eq!(compile("var a; var b; var c; var d; var e;\na = (a + b) * ((c + d) * (e + (a * (b + c))));", 5).unwrap_err(),
+            CompileError { line: 2, message: "expression needs more registers than are free".into() });
+    }
+}
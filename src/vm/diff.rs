@@ -0,0 +1,176 @@
+//! Differential execution: run two VMs side by side and find where their
+//! control flow first parts ways, e.g. day19's program with r0=0 and r0=1.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use super::{Observer, RunResult, NREGS, VM};
+
+/// The first point where the two runs would execute different addresses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Instructions both VMs executed before this point.
+    pub step: usize,
+    /// Address of the last instruction they executed in common, if any.
+    pub last_common: Option<usize>,
+    /// Address each VM executes next; `None` if it has halted.
+    pub next: [Option<usize>; 2],
+    pub regs: [[usize; NREGS]; 2],
+}
+
+/// What one VM did after the divergence.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathSummary {
+    pub steps: usize,
+    pub halted: bool,
+    /// How many times each address ran.
+    pub visits: BTreeMap<usize, usize>,
+    pub final_regs: [usize; NREGS],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffReport {
+    /// `None` if the runs stayed in step until both halted or the step
+    /// limit ran out.
+    pub divergence: Option<Divergence>,
+    pub paths: [PathSummary; 2],
+}
+impl DiffReport {
+    /// Addresses that only one side (0 or 1) visited after diverging.
+    pub fn exclusive(&self, side: usize) -> BTreeSet<usize> {
+        let other = &self.paths[1 - side].visits;
+        self.paths[side].visits.keys().filter(|a| !other.contains_key(a)).copied().collect()
+    }
+}
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(d) = &self.divergence else {
+            return write!(f, "no divergence; final registers {:?} and {:?}",
+                self.paths[0].final_regs, self.paths[1].final_regs);
+        };
+        let show = |a: Option<usize>| a.map_or("halt".to_owned(), |a| a.to_string());
+        match d.last_common {
+            Some(addr) => writeln!(f, "diverged after {} steps, at address {addr}", d.step)?,
+            None => writeln!(f, "diverged before the first step")?,
+        }
+        writeln!(f, "  A continues at {}, registers {:?}", show(d.next[0]), d.regs[0])?;
+        writeln!(f, "  B continues at {}, registers {:?}", show(d.next[1]), d.regs[1])?;
+        for (side, name) in ["A", "B"].iter().enumerate() {
+            let p = &self.paths[side];
+            writeln!(f, "  {name} then ran {} steps{}, ending with registers {:?}",
+                p.steps, if p.halted { " and halted" } else { "" }, p.final_regs)?;
+            writeln!(f, "    addresses only {name} visited: {:?}", self.exclusive(side))?;
+        }
+        Ok(())
+    }
+}
+
+fn next_addr<O: Observer>(vm: &VM<O>) -> Option<usize> {
+    let addr = vm.r[vm.ip];
+    (addr < vm.prog.len()).then_some(addr)
+}
+
+// Whether `advance` would execute an instruction.
+fn can_step<O: Observer>(vm: &VM<O>) -> bool {
+    next_addr(vm).is_some() && vm.fuel != Some(0)
+}
+
+// Executes one instruction, passing over any breakpoints; false if the VM
+// can't go on (halted, out of fuel or an error).
+fn advance<O: Observer>(vm: &mut VM<O>) -> bool {
+    loop {
+        match vm.step() {
+            RunResult::Ok => return true,
+            RunResult::Break(_) => (),
+            _ => return false,
+        }
+    }
+}
+
+fn summarize<O: Observer>(vm: &mut VM<O>, max_steps: usize) -> PathSummary {
+    let mut summary = PathSummary::default();
+    loop {
+        let Some(addr) = next_addr(vm) else {
+            summary.halted = true;
+            break;
+        };
+        if summary.steps == max_steps || !advance(vm) {
+            break;
+        }
+        *summary.visits.entry(addr).or_default() += 1;
+        summary.steps += 1;
+    }
+    summary.final_regs = vm.r;
+    summary
+}
+
+/// Steps `a` and `b` in lockstep until the addresses they execute differ,
+/// then lets each run on alone to summarize where it goes. `max_steps`
+/// bounds both the lockstep phase and each run after it.
+pub fn diff<A: Observer, B: Observer>(a: &mut VM<A>, b: &mut VM<B>, max_steps: usize) -> DiffReport {
+    let mut step = 0;
+    let mut last_common = None;
+    let mut divergence = None;
+    while step < max_steps {
+        let next = [next_addr(a), next_addr(b)];
+        if next[0] != next[1] {
+            divergence = Some(Divergence { step, last_common, next, regs: [a.r, b.r] });
+            break;
+        }
+        // check both first, so one isn't left a step ahead of the other
+        if !can_step(a) || !can_step(b) || !advance(a) || !advance(b) {
+            break;
+        }
+        last_common = next[0];
+        step += 1;
+    }
+    let max_after = if divergence.is_some() { max_steps } else { 0 };
+    DiffReport {
+        divergence,
+        paths: [summarize(a, max_after), summarize(b, max_after)],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vm::ProgramItem;
+
+    fn vm(r0: usize) -> VM {
+        let prog: Vec<ProgramItem> = "#ip 3\nseti 7 0 1\neqrr 1 0 2\naddr 2 3 3\naddi 4 1 4\nseti 9 0 3"
+            .lines().map(|l| l.parse().unwrap()).collect();
+        let mut vm = VM::new();
+        vm.load(&prog);
+        vm.r[0] = r0;
+        vm
+    }
+
+    #[test]
+    fn diverge() {
+        let report = diff(&mut vm(7), &mut vm(5), 100);
+        assert_eq!(report.divergence, Some(Divergence {
+            step: 3,
+            last_common: Some(2),
+            next: [Some(4), Some(3)],
+            regs: [[7, 7, 1, 4, 0, 0], [5, 7, 0, 3, 0, 0]],
+        }));
+        assert_eq!(report.paths[0].visits, BTreeMap::from([(4, 1)]));
+        assert_eq!(report.paths[1].visits, BTreeMap::from([(3, 1), (4, 1)]));
+        assert!(report.paths[0].halted && report.paths[1].halted);
+        assert_eq!(report.exclusive(0), BTreeSet::new());
+        assert_eq!(report.exclusive(1), BTreeSet::from([3]));
+        assert_eq!(report.paths[1].final_regs, [5, 7, 0, 10, 1, 0]);
+
+        let report = diff(&mut vm(7), &mut vm(7), 100);
+        assert_eq!(report.divergence, None);
+        assert!(report.paths[0].halted);
+        assert_eq!(report.paths[0].final_regs, [7, 7, 1, 10, 0, 0]);
+
+        // B running out of fuel stops both in the same place
+        let (mut a, mut b) = (vm(7), vm(7));
+        b.fuel = Some(2);
+        let report = diff(&mut a, &mut b, 100);
+        assert_eq!(report.divergence, None);
+        assert_eq!(a.r, b.r);
+        assert_eq!(a.r[3], 2);
+    }
+}
@@ -0,0 +1,362 @@
+//! Checks whether two elfcode programs compute the same final registers.
+//!
+//! Each program's opening straight-line block (up to the first instruction
+//! that touches the ip register) is evaluated symbolically. If both programs
+//! are entirely straight-line, matching normalized results prove them
+//! equivalent; so do openings that agree on every register when both
+//! programs carry on with the same code from the same address, as long as
+//! that code can't jump back to where the programs differ. Otherwise the
+//! programs are run from every combination of small input values and then
+//! from random ones, each under a fuel limit, looking for a counterexample.
+
+use std::fmt;
+use std::vec::Vec;
+use super::superopt::{exec, registers, Rng};
+use super::{Instruction, ProgramItem, NREGS, OPERATIONS, VM};
+
+pub struct Options {
+    /// Registers given test values; by default all but the ip registers.
+    pub inputs: Option<Vec<usize>>,
+    /// Registers whose final values must agree; by default all but the ip
+    /// registers.
+    pub compare: Option<Vec<usize>>,
+    /// Exhaustive testing tries every input value from 0 to this.
+    pub domain_max: usize,
+    pub random_tests: usize,
+    /// Steps each run may take before it's given up on.
+    pub fuel: usize,
+    pub seed: u64,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            inputs: None,
+            compare: None,
+            domain_max: 3,
+            random_tests: 1000,
+            fuel: 100_000,
+            seed: 2018,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counterexample {
+    /// Each program's registers at the start: its `#reg` values with the
+    /// inputs laid over them.
+    pub initial: [[usize; NREGS]; 2],
+    pub regs: [[usize; NREGS]; 2],
+    /// The compared registers that ended up different.
+    pub differing: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// Their results are symbolically identical, or their straight-line
+    /// openings are and the code after them is the same.
+    Proven,
+    /// Every test agreed. Tests where either program ran out of fuel or
+    /// failed don't count either way and are tallied as inconclusive.
+    NoDifferenceFound { tests: usize, inconclusive: usize },
+    Different(Counterexample),
+}
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Proven => write!(f, "equivalent (proven symbolically)"),
+            Verdict::NoDifferenceFound { tests, inconclusive } =>
+                write!(f, "no difference found in {tests} tests ({inconclusive} inconclusive)"),
+            Verdict::Different(c) if c.initial[0] == c.initial[1] =>
+                write!(f, "different: starting from {:?}, A ends with {:?} and B with {:?} (registers {:?} differ)",
+                    c.initial[0], c.regs[0], c.regs[1], c.differing),
+            Verdict::Different(c) =>
+                write!(f, "different: starting A from {:?} and B from {:?}, A ends with {:?} and B with {:?} (registers {:?} differ)",
+                    c.initial[0], c.initial[1], c.regs[0], c.regs[1], c.differing),
+        }
+    }
+}
+
+/// Symbolic register value. Operands of the associative and commutative
+/// ops are flattened and sorted so that equal expressions compare equal.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Sym {
+    Const(usize),
+    Input(usize),
+    Add(Vec<Sym>),
+    Mul(Vec<Sym>),
+    And(Vec<Sym>),
+    Or(Vec<Sym>),
+    Gt(Box<Sym>, Box<Sym>),
+    Eq(Box<Sym>, Box<Sym>),
+}
+
+impl Sym {
+    // Builds a normalized add/mul/and/or node.
+    fn assoc(kind: &str, a: Sym, b: Sym) -> Sym {
+        let (unit, fold): (usize, fn(usize, usize) -> usize) = match kind {
+            "add" => (0, usize::wrapping_add),
+            "mul" => (1, usize::wrapping_mul),
+            "ban" => (usize::MAX, |x, y| x & y),
+            _ => (0, |x, y| x | y),
+        };
+        let mut terms = Vec::new();
+        for s in [a, b] {
+            match (kind, s) {
+                ("add", Sym::Add(t)) | ("mul", Sym::Mul(t)) |
+                ("ban", Sym::And(t)) | ("bor", Sym::Or(t)) => terms.extend(t),
+                (_, s) => terms.push(s),
+            }
+        }
+        let mut acc = unit;
+        terms.retain(|t| match t {
+            Sym::Const(c) => {
+                acc = fold(acc, *c);
+                false
+            },
+            _ => true,
+        });
+        terms.sort();
+        if kind == "ban" || kind == "bor" {
+            terms.dedup();
+        }
+        // absorbing elements
+        if (kind == "mul" || kind == "ban") && acc == 0 || kind == "bor" && acc == usize::MAX {
+            return Sym::Const(acc);
+        }
+        if acc != unit || terms.is_empty() {
+            terms.push(Sym::Const(acc));
+        }
+        if terms.len() == 1 {
+            return terms.pop().unwrap();
+        }
+        match kind {
+            "add" => Sym::Add(terms),
+            "mul" => Sym::Mul(terms),
+            "ban" => Sym::And(terms),
+            _ => Sym::Or(terms),
+        }
+    }
+    fn gt(a: Sym, b: Sym) -> Sym {
+        match (&a, &b) {
+            (Sym::Const(x), Sym::Const(y)) => Sym::Const((x > y) as usize),
+            _ if a == b => Sym::Const(0),
+            _ => Sym::Gt(Box::new(a), Box::new(b)),
+        }
+    }
+    fn eq(a: Sym, b: Sym) -> Sym {
+        match (&a, &b) {
+            (Sym::Const(x), Sym::Const(y)) => Sym::Const((x == y) as usize),
+            _ if a == b => Sym::Const(1),
+            _ if a < b => Sym::Eq(Box::new(a), Box::new(b)),
+            _ => Sym::Eq(Box::new(b), Box::new(a)),
+        }
+    }
+    fn apply(inst: &Instruction, state: &[Sym; NREGS]) -> Sym {
+        let op = &OPERATIONS[&inst.opcode];
+        let a = if op.a_immed { Sym::Const(inst.a) } else { state[inst.a].clone() };
+        let b = if op.b_immed { Sym::Const(inst.b) } else { state[inst.b].clone() };
+        match &inst.opcode[..3] {
+            "set" => a,
+            "gti" | "gtr" => Sym::gt(a, b),
+            "eqi" | "eqr" => Sym::eq(a, b),
+            kind => Sym::assoc(kind, a, b),
+        }
+    }
+}
+
+// A loaded program: its ip register, the state `load` leaves behind, and
+// the instructions.
+struct Loaded {
+    ip: usize,
+    init: [usize; NREGS],
+    prog: Vec<Instruction>,
+}
+impl Loaded {
+    fn new(program: &[ProgramItem]) -> Self {
+        let mut vm = VM::new();
+        vm.load(program);
+        Self { ip: vm.ip, init: vm.r, prog: vm.prog }
+    }
+    // The instructions that run in order from the entry point, up to the
+    // first one that touches the ip register, and the address after them.
+    fn opening(&self) -> (&[Instruction], usize) {
+        let start = self.init[self.ip].min(self.prog.len());
+        let len = self.prog[start..].iter().take_while(|i| !registers(i).contains(&self.ip)).count();
+        (&self.prog[start..start + len], start + len)
+    }
+    // Whether the code from `from` on might send the ip below `from`. Only
+    // jumps to a constant or forward from the current address are known not
+    // to.
+    fn may_jump_below(&self, from: usize) -> bool {
+        let ip = self.ip;
+        self.prog[from..].iter().filter(|inst| inst.c == ip).any(|inst| match inst.opcode {
+            "seti" => inst.a + 1 < from,
+            "setr" => inst.a != ip,
+            "addi" => inst.a != ip || inst.b.checked_add(self.prog.len()).is_none(),
+            "addr" => inst.a != ip && inst.b != ip,
+            _ => true,
+        })
+    }
+    // The registers a run starts with.
+    fn initial(&self, inputs: &[usize], values: &[usize]) -> [usize; NREGS] {
+        let mut r = self.init;
+        for (reg, v) in inputs.iter().zip(values) {
+            r[*reg] = *v;
+        }
+        r
+    }
+    fn symbolic(&self, code: &[Instruction], inputs: &[usize]) -> [Sym; NREGS] {
+        let mut state: [Sym; NREGS] = std::array::from_fn(|r|
+            if inputs.contains(&r) { Sym::Input(r) } else { Sym::Const(self.init[r]) });
+        for inst in code {
+            state[inst.c] = Sym::apply(inst, &state);
+        }
+        state
+    }
+    // Runs to a halt like the VM would, but with wrapping arithmetic since
+    // the inputs are arbitrary; None if it runs out of fuel.
+    fn run(&self, inputs: &[usize], values: &[usize], fuel: usize) -> Option<[usize; NREGS]> {
+        let mut r = self.initial(inputs, values);
+        for _ in 0..fuel {
+            let Some(inst) = self.prog.get(r[self.ip]) else {
+                return Some(r);
+            };
+            exec(inst, &mut r);
+            r[self.ip] = r[self.ip].wrapping_add(1);
+        }
+        (r[self.ip] >= self.prog.len()).then_some(r)
+    }
+}
+
+/// Compares two programs as described in the module docs.
+pub fn check(a: &[ProgramItem], b: &[ProgramItem], opts: &Options) -> Verdict {
+    let progs = [Loaded::new(a), Loaded::new(b)];
+    let not_ip: Vec<usize> = (0..NREGS).filter(|r| *r != progs[0].ip && *r != progs[1].ip).collect();
+    let inputs = opts.inputs.clone().unwrap_or_else(|| not_ip.clone());
+    let compare = opts.compare.clone().unwrap_or(not_ip);
+
+    let (code_a, end_a) = progs[0].opening();
+    let (code_b, end_b) = progs[1].opening();
+    let sa = progs[0].symbolic(code_a, &inputs);
+    let sb = progs[1].symbolic(code_b, &inputs);
+    if end_a == progs[0].prog.len() && end_b == progs[1].prog.len() {
+        if compare.iter().all(|r| sa[*r] == sb[*r]) {
+            return Verdict::Proven;
+        }
+    }
+    else if progs[0].ip == progs[1].ip && end_a == end_b && progs[0].prog[end_a..] == progs[1].prog[end_b..]
+        && (0..NREGS).all(|r| r == progs[0].ip || sa[r] == sb[r]) {
+        // where the code the two have in common starts
+        let shared = (0..end_a).rev().take_while(|i| progs[0].prog[*i] == progs[1].prog[*i]).count();
+        if !progs[0].may_jump_below(end_a - shared) {
+            return Verdict::Proven;
+        }
+    }
+
+    let mut tests = 0;
+    let mut inconclusive = 0;
+    let mut test = |values: &[usize]| -> Option<Counterexample> {
+        tests += 1;
+        let ra = progs[0].run(&inputs, values, opts.fuel);
+        let rb = progs[1].run(&inputs, values, opts.fuel);
+        let (Some(ra), Some(rb)) = (ra, rb) else {
+            inconclusive += 1;
+            return None;
+        };
+        let differing: Vec<usize> = compare.iter().filter(|r| ra[**r] != rb[**r]).copied().collect();
+        if differing.is_empty() {
+            return None;
+        }
+        let initial = [progs[0].initial(&inputs, values), progs[1].initial(&inputs, values)];
+        Some(Counterexample { initial, regs: [ra, rb], differing })
+    };
+
+    let domain = opts.domain_max + 1;
+    if domain.checked_pow(inputs.len() as u32).is_some() {
+        let mut values = vec![0; inputs.len()];
+        'values: loop {
+            if let Some(c) = test(&values) {
+                return Verdict::Different(c);
+            }
+            for v in values.iter_mut() {
+                *v += 1;
+                if *v < domain {
+                    continue 'values;
+                }
+                *v = 0;
+            }
+            break;
+        }
+    }
+    let mut rng = Rng::new(opts.seed);
+    for _ in 0..opts.random_tests {
+        let values: Vec<usize> = inputs.iter().map(|_| rng.value()).collect();
+        if let Some(c) = test(&values) {
+            return Verdict::Different(c);
+        }
+    }
+    Verdict::NoDifferenceFound { tests, inconclusive }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vm::compile::compile;
+
+    fn program(src: &str) -> Vec<ProgramItem> {
+        src.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn equivalence() {
+        let opts = Options::default();
+        assert_eq!(check(&program("#ip 5\naddi 1 2 1\naddi 1 3 1\nmuli 2 0 3"),
+                         &program("#ip 5\nseti 0 0 3\naddi 1 5 1"), &opts),
+                   Verdict::Proven);
+        // distributivity is beyond the normalizer, so this one comes down to testing
+        assert_eq!(check(&program("#ip 5\naddr 1 2 3\nmuli 3 2 3"),
+                         &program("#ip 5\nmuli 1 2 3\nmuli 2 2 4\naddr 3 4 3\nseti 0 0 4"),
+                         &Options { compare: Some(vec![3]), ..Options::default() }),
+                   Verdict::NoDifferenceFound { tests: 2024, inconclusive: 0 });
+        assert_eq!(check(&program("#ip 5\nmulr 1 1 2"), &program("#ip 5\naddr 1 1 2"), &opts),
+                   Verdict::Different(Counterexample {
+                       initial: [[0, 1, 0, 0, 0, 0]; 2],
+                       regs: [[0, 1, 1, 0, 0, 1], [0, 1, 2, 0, 0, 1]],
+                       differing: vec![2],
+                   }));
+
+        // the counterexample starts from the #reg values too
+        let opts = Options { inputs: Some(vec![1]), compare: Some(vec![2]), ..Options::default() };
+        assert_eq!(check(&program("#ip 5\n#reg 4 3\naddr 1 4 2"), &program("#ip 5\naddi 1 2 2"), &opts),
+                   Verdict::Different(Counterexample {
+                       initial: [[0, 0, 0, 0, 3, 0], [0; NREGS]],
+                       regs: [[0, 0, 3, 0, 3, 1], [0, 0, 2, 0, 0, 1]],
+                       differing: vec![2],
+                   }));
+
+        // different openings into the same loop
+        let looping = "addi 2 1 2\ngtrr 0 2 4\naddr 4 5 5\nseti 99 0 5\nseti 2 0 5";
+        assert_eq!(check(&program(&format!("#ip 5\naddi 1 2 1\naddi 1 3 1\nseti 0 0 3\n{looping}")),
+                         &program(&format!("#ip 5\naddi 1 5 1\nmuli 3 0 3\nseti 0 0 3\n{looping}")),
+                         &Options::default()),
+                   Verdict::Proven);
+        assert!(matches!(check(&program(&format!("#ip 5\naddi 1 2 1\nseti 0 0 3\n{looping}")),
+                               &program(&format!("#ip 5\naddi 1 5 1\nseti 0 0 3\n{looping}")),
+                               &Options::default()),
+                         Verdict::Different(_)));
+        // the same registers at the join, but the loop goes back into the openings
+        let back = "addi 2 1 2\ngtri 2 1 4\naddr 4 5 5\nseti 0 0 5";
+        let verdict = check(&program(&format!("#ip 5\naddi 1 2 1\naddi 1 3 1\n{back}")),
+                            &program(&format!("#ip 5\naddi 1 5 1\nmuli 3 1 3\n{back}")),
+                            &Options::default());
+        assert!(matches!(verdict, Verdict::Different(ref c) if c.regs[0][1] != c.regs[1][1]), "{verdict}");
+
+        // a loop against its closed form
+        let looped = compile("var n: r0; var x: r1; var i: r2; while (i < n) { x = x + 2; i = i + 1; }", 5).unwrap();
+        let direct = compile("var n: r0; var x: r1; x = n * 2;", 5).unwrap();
+        let opts = Options { inputs: Some(vec![0]), compare: Some(vec![1]), random_tests: 20, ..Options::default() };
+        assert!(matches!(check(&looped, &direct, &opts), Verdict::NoDifferenceFound { tests: 24, .. }));
+        let direct = compile("var n: r0; var x: r1; x = n * 2 + 1;", 5).unwrap();
+        assert!(matches!(check(&looped, &direct, &opts), Verdict::Different(c) if c.initial == [[0; NREGS]; 2]));
+    }
+}
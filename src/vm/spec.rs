@@ -0,0 +1,210 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+use super::{validate, Diagnostic, ProgramItem, RegMismatch, RunResult, Severity, NREGS, VM};
+
+/// Step limit for specs that don't set their own `#fuel`, so a runaway
+/// program fails instead of hanging the test run.
+pub const DEFAULT_FUEL: usize = 10_000_000;
+
+/// Why a spec didn't pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    Parse(usize),
+    Invalid(Vec<Diagnostic>),
+    /// Ran out of steps with the next instruction at `ip`; `diffs` are the
+    /// `#expect` mismatches at that point.
+    OutOfFuel { steps: usize, ip: usize, diffs: Vec<RegMismatch> },
+    Error(&'static str),
+    Registers(Vec<RegMismatch>),
+    Breaks { expected: Vec<usize>, actual: Vec<usize> },
+}
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Parse(line) => write!(f, "line {line}: invalid input"),
+            Failure::Invalid(diags) => {
+                write!(f, "program is invalid:")?;
+                diags.iter().try_for_each(|d| write!(f, "\n  {d}"))
+            },
+            Failure::OutOfFuel { steps, ip, diffs } => {
+                write!(f, "did not halt within {steps} steps (stopped at address {ip})")?;
+                write_diffs(f, diffs)
+            },
+            Failure::Error(e) => write!(f, "error while running program: {e}"),
+            Failure::Registers(diffs) => {
+                write!(f, "final registers differ:")?;
+                write_diffs(f, diffs)
+            },
+            Failure::Breaks { expected, actual } =>
+                write!(f, "breakpoint sequence differs:\n  expected {expected:?}\n  got      {actual:?}"),
+        }
+    }
+}
+
+fn write_diffs(f: &mut fmt::Formatter<'_>, diffs: &[RegMismatch]) -> fmt::Result {
+    diffs.iter().try_for_each(|m|
+        write!(f, "\n  r{}: expected {}, got {}", m.reg, m.expected, m.actual))
+}
+
+pub struct SpecResult {
+    pub steps: usize,
+    pub regs: [usize; NREGS],
+    pub failures: Vec<Failure>,
+}
+impl SpecResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Finds every `.elf` file under `dir`, sorted by path.
+pub fn discover(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            found.extend(discover(&path)?);
+        }
+        else if path.extension().is_some_and(|ext| ext == "elf") {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+// The lines of a spec that hold program items, numbered from 1: blank lines
+// and `//` comments are skipped.
+fn item_lines(src: &str) -> impl Iterator<Item = (usize, &str)> {
+    src.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with("//"))
+}
+
+/// Parses a spec file, skipping blank lines and `//` comments; on failure
+/// returns the 1-based line that didn't parse.
+pub fn parse(src: &str) -> Result<Vec<ProgramItem>, usize> {
+    item_lines(src)
+        .map(|(line_no, line)| line.parse().map_err(|_| line_no))
+        .collect()
+}
+
+pub fn run_file(path: &Path) -> io::Result<SpecResult> {
+    let src = fs::read_to_string(path)?;
+    Ok(match parse(&src) {
+        Ok(program) => {
+            let mut result = run_spec(&program);
+            // diagnostics count program items; report source lines instead
+            let lines: Vec<usize> = item_lines(&src).map(|(line_no, _)| line_no).collect();
+            for failure in result.failures.iter_mut() {
+                if let Failure::Invalid(diags) = failure {
+                    for d in diags.iter_mut() {
+                        d.line = lines.get(d.line.wrapping_sub(1)).copied().unwrap_or(d.line);
+                    }
+                }
+            }
+            result
+        },
+        Err(line) => SpecResult { steps: 0, regs: [0; NREGS], failures: vec![Failure::Parse(line)] },
+    })
+}
+
+/// Validates and runs a program, then checks its `#expect` and
+/// `#expect_break` directives.
+pub fn run_spec(program: &[ProgramItem]) -> SpecResult {
+    let errors: Vec<Diagnostic> = validate(program).into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect();
+    if !errors.is_empty() {
+        return SpecResult { steps: 0, regs: [0; NREGS], failures: vec![Failure::Invalid(errors)] };
+    }
+
+    let mut vm = VM::new();
+    vm.load(program);
+    let fuel = *vm.fuel.get_or_insert(DEFAULT_FUEL);
+    let mut breaks = Vec::new();
+    let mut failures = Vec::new();
+    loop {
+        match vm.run() {
+            RunResult::Break(_) => breaks.push(vm.r[vm.ip]),
+            RunResult::Halt => {
+                let diffs = vm.check_expected();
+                if !diffs.is_empty() {
+                    failures.push(Failure::Registers(diffs));
+                }
+                if !vm.expected_breaks.is_empty() && vm.expected_breaks != breaks {
+                    failures.push(Failure::Breaks { expected: vm.expected_breaks.clone(), actual: breaks });
+                }
+                break;
+            },
+            RunResult::OutOfFuel => {
+                failures.push(Failure::OutOfFuel { steps: fuel, ip: vm.r[vm.ip], diffs: vm.check_expected() });
+                break;
+            },
+            RunResult::Err(e) => {
+                failures.push(Failure::Error(e));
+                break;
+            },
+            RunResult::Ok => unreachable!(),
+        }
+    }
+    SpecResult {
+        steps: fuel - vm.fuel.unwrap(),
+        regs: vm.r,
+        failures,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn elf_specs() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("elf");
+        let mut failed = Vec::new();
+        for path in discover(&dir).unwrap() {
+            let result = run_file(&path).unwrap();
+            for f in result.failures {
+                failed.push(format!("{}: {f}", path.display()));
+            }
+        }
+        assert!(failed.is_empty(), "{}", failed.join("\n"));
+    }
+
+    #[test]
+    fn failures() {
+        let program = parse("#ip 0\n#expect 1 2\n#break 1\n#expect_break 1\nseti 5 0 1\nsetr 1 0 2").unwrap();
+        let result = run_spec(&program);
+        assert_eq!(result.steps, 2);
+        assert_eq!(result.failures, vec![
+            Failure::Registers(vec![RegMismatch { reg: 1, expected: 2, actual: 5 }]),
+        ]);
+
+        let program = parse("#ip 0\n#break 1\n#expect_break 0\nseti 5 0 1\nsetr 1 0 2").unwrap();
+        let result = run_spec(&program);
+        assert_eq!(result.failures, vec![
+            Failure::Breaks { expected: vec![0], actual: vec![1] },
+        ]);
+
+        let program = parse("#ip 0\n#fuel 100\n#expect 1 7\nseti 0 0 2\naddi 1 1 1\nseti 0 0 0").unwrap();
+        let result = run_spec(&program);
+        assert_eq!(result.steps, 100);
+        assert_eq!(result.failures, vec![Failure::OutOfFuel {
+            steps: 100,
+            ip: 2,
+            diffs: vec![RegMismatch { reg: 1, expected: 7, actual: 50 }],
+        }]);
+        assert_eq!(result.failures[0].to_string(),
+            "did not halt within 100 steps (stopped at address 2)\n  r1: expected 7, got 50");
+
+        assert_eq!(parse("#ip 0\nnope 1 2 3").err(), Some(2));
+        let program = parse("// counts to one\n\n#ip 0\n  // then stops\naddi 1 1 1\n").unwrap();
+        assert_eq!(program.len(), 2);
+        assert_eq!(parse("// comment\n\nnope 1 2 3").err(), Some(3));
+    }
+}
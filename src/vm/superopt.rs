@@ -0,0 +1,298 @@
+//! A superoptimizer for straight-line elfcode: given a short window of
+//! instructions, it enumerates every shorter sequence over the 16 ops and
+//! keeps the first one that leaves all registers the same as the window
+//! does. Candidates are checked against a few random states, then
+//! exhaustively over small register values, then against many more random
+//! states; that's strong evidence of equivalence but not a proof.
+
+use std::vec::Vec;
+use super::{Instruction, Meta, ProgramItem, NREGS, OPERATIONS};
+
+pub struct Options {
+    /// Longest replacement to try; the search is exponential in this.
+    pub max_len: usize,
+    /// Number of random register states a candidate must agree on.
+    pub random_tests: usize,
+    /// Most register states to try in the exhaustive check.
+    pub exhaustive_limit: usize,
+    pub seed: u64,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            max_len: 2,
+            random_tests: 1000,
+            exhaustive_limit: 100_000,
+            seed: 2018,
+        }
+    }
+}
+
+/// xorshift64*, so test states are reproducible without pulling in a crate.
+pub(crate) struct Rng(u64);
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    /// A register value, skewed towards the small numbers and single bits
+    /// that comparisons and masks care about.
+    pub(crate) fn value(&mut self) -> usize {
+        let n = self.next();
+        match n % 4 {
+            0 => (n >> 8) as usize % 8,
+            1 => (n >> 8) as usize % 256,
+            2 => 1 << ((n >> 8) % usize::BITS as u64),
+            _ => self.next() as usize,
+        }
+    }
+    pub(crate) fn state(&mut self) -> [usize; NREGS] {
+        std::array::from_fn(|_| self.value())
+    }
+}
+
+/// Registers that matter to an instruction: those it reads, and the one it writes.
+pub(crate) fn registers(inst: &Instruction) -> Vec<usize> {
+    let op = &OPERATIONS[&inst.opcode];
+    let mut regs = vec![inst.c];
+    if !op.a_immed {
+        regs.push(inst.a);
+    }
+    if !op.b_immed {
+        regs.push(inst.b);
+    }
+    regs
+}
+
+/// Immediates an instruction actually uses (setr and seti ignore B).
+pub(crate) fn immediates(inst: &Instruction) -> Vec<usize> {
+    let op = &OPERATIONS[&inst.opcode];
+    let mut imms = Vec::new();
+    if op.a_immed {
+        imms.push(inst.a);
+    }
+    if op.b_immed && !inst.opcode.starts_with("set") {
+        imms.push(inst.b);
+    }
+    imms
+}
+
+/// Executes one instruction the way `VM::exec` does, except that addition
+/// and multiplication wrap: the arbitrary register values tried here would
+/// otherwise overflow, which panics in a debug build.
+pub(crate) fn exec(inst: &Instruction, r: &mut [usize; NREGS]) {
+    let op = &OPERATIONS[&inst.opcode];
+    let a = if op.a_immed { inst.a } else { r[inst.a] };
+    let b = if op.b_immed { inst.b } else { r[inst.b] };
+    r[inst.c] = match &inst.opcode[..3] {
+        "add" => a.wrapping_add(b),
+        "mul" => a.wrapping_mul(b),
+        _ => (op.op)(a, b),
+    };
+}
+
+fn run(seq: &[Instruction], state: &[usize; NREGS]) -> [usize; NREGS] {
+    let mut r = *state;
+    seq.iter().for_each(|inst| exec(inst, &mut r));
+    r
+}
+
+// Opcodes in the order candidates are tried, so that the plainest of several
+// equivalent replacements is the one reported.
+const SEARCH_ORDER: [&str; 16] = [
+    "seti", "setr", "addi", "addr", "muli", "mulr", "bani", "banr",
+    "bori", "borr", "gtri", "gtir", "gtrr", "eqri", "eqir", "eqrr",
+];
+
+// Every instruction over the given registers and constants, skipping the
+// mirror images of commutative ops.
+fn alphabet(regs: &[usize], consts: &[usize]) -> Vec<Instruction> {
+    let mut insts = Vec::new();
+    for (opcode, op) in SEARCH_ORDER.iter().map(|name| OPERATIONS.get_key_value(name).unwrap()) {
+        let commutative = ["addr", "mulr", "banr", "borr", "eqrr"].contains(opcode);
+        let a_vals = if op.a_immed { consts } else { regs };
+        let b_vals: &[usize] = if opcode.starts_with("set") { &[0] } else if op.b_immed { consts } else { regs };
+        for &a in a_vals {
+            for &b in b_vals.iter().filter(|b| !commutative || a <= **b) {
+                for &c in regs {
+                    insts.push(Instruction { opcode, a, b, c });
+                }
+            }
+        }
+    }
+    insts
+}
+
+struct Tester {
+    quick: Vec<([usize; NREGS], [usize; NREGS])>,
+    thorough: Vec<([usize; NREGS], [usize; NREGS])>,
+}
+impl Tester {
+    fn new(window: &[Instruction], regs: &[usize], opts: &Options) -> Self {
+        let mut rng = Rng::new(opts.seed);
+        let expect = |state: [usize; NREGS]| (state, run(window, &state));
+
+        let quick = (0..16).map(|_| expect(rng.state())).collect();
+
+        // exhaustive over small values and values next to the window's
+        // constants, falling back to just the small ones if that's too many
+        let mut domain: Vec<usize> = (0..4)
+            .chain(window.iter().flat_map(immediates)
+                .flat_map(|c| [c.wrapping_sub(1), c, c.wrapping_add(1)]))
+            .collect();
+        domain.sort_unstable();
+        domain.dedup();
+        let count = |d: &[usize]| d.len().checked_pow(regs.len() as u32);
+        if count(&domain).is_none_or(|n| n > opts.exhaustive_limit) {
+            domain.truncate(4);
+        }
+        let mut thorough = Vec::new();
+        if count(&domain).is_some_and(|n| n <= opts.exhaustive_limit) {
+            let mut idx = vec![0; regs.len()];
+            'states: loop {
+                let mut state = [0; NREGS];
+                for (r, i) in regs.iter().zip(&idx) {
+                    state[*r] = domain[*i];
+                }
+                thorough.push(expect(state));
+                for i in idx.iter_mut() {
+                    *i += 1;
+                    if *i < domain.len() {
+                        continue 'states;
+                    }
+                    *i = 0;
+                }
+                break;
+            }
+        }
+        thorough.extend((0..opts.random_tests).map(|_| expect(rng.state())));
+        Self { quick, thorough }
+    }
+    fn accepts(&self, seq: &[Instruction]) -> bool {
+        self.quick.iter().chain(self.thorough.iter())
+            .all(|(state, expected)| run(seq, state) == *expected)
+    }
+}
+
+/// Finds the shortest sequence that's equivalent to `window` and shorter
+/// than it, up to `opts.max_len` instructions. `window` must be straight-line
+/// code: it shouldn't touch the ip register.
+pub fn shorter_equivalent(window: &[Instruction], opts: &Options) -> Option<Vec<Instruction>> {
+    let mut regs: Vec<usize> = window.iter().flat_map(registers).collect();
+    regs.sort_unstable();
+    regs.dedup();
+    let mut consts: Vec<usize> = window.iter().flat_map(immediates).chain([0, 1]).collect();
+    consts.sort_unstable();
+    consts.dedup();
+    // also allow constants that folding two of these together would give
+    let folded: Vec<usize> = consts.iter()
+        .flat_map(|x| consts.iter().flat_map(move |y|
+            [x.wrapping_add(*y), x.wrapping_mul(*y), x & y, x | y]))
+        .collect();
+    consts.extend(folded);
+    consts.sort_unstable();
+    consts.dedup();
+
+    let alphabet = alphabet(&regs, &consts);
+    let tester = Tester::new(window, &regs, opts);
+    for len in 0..window.len().min(opts.max_len + 1) {
+        let mut idx = vec![0; len];
+        let mut seq: Vec<Instruction> = idx.iter().map(|i| alphabet[*i]).collect();
+        'candidates: loop {
+            if tester.accepts(&seq) {
+                return Some(seq);
+            }
+            for (pos, i) in idx.iter_mut().enumerate() {
+                *i += 1;
+                if *i < alphabet.len() {
+                    seq[pos] = alphabet[*i];
+                    continue 'candidates;
+                }
+                *i = 0;
+                seq[pos] = alphabet[0];
+            }
+            break;
+        }
+    }
+    None
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replacement {
+    /// Address of the first replaced instruction.
+    pub addr: usize,
+    pub original: Vec<Instruction>,
+    pub replacement: Vec<Instruction>,
+}
+
+/// Slides a window of up to `window` instructions over the straight-line
+/// parts of a program and reports every shorter equivalent found. Applying
+/// a replacement shifts later addresses, so jumps need fixing up by hand.
+pub fn scan(program: &[ProgramItem], window: usize, opts: &Options) -> Vec<Replacement> {
+    let ip_reg = program.iter().rev().find_map(|item| match item {
+        ProgramItem::Meta(Meta::MapIp(reg)) => Some(*reg),
+        _ => None,
+    }).unwrap_or(0);
+    let instrs: Vec<Instruction> = program.iter().filter_map(|item| match item {
+        ProgramItem::Instr(inst) => Some(*inst),
+        _ => None,
+    }).collect();
+    let straight = |inst: &Instruction| !registers(inst).contains(&ip_reg);
+
+    let mut found = Vec::new();
+    let mut addr = 0;
+    while addr < instrs.len() {
+        let run = instrs[addr..].iter().take(window).take_while(|i| straight(i)).count();
+        let hit = (1..=run).rev().find_map(|w| {
+            let original = &instrs[addr..addr + w];
+            shorter_equivalent(original, opts).map(|replacement| Replacement {
+                addr,
+                original: original.to_vec(),
+                replacement,
+            })
+        });
+        match hit {
+            Some(rep) => {
+                addr += rep.original.len();
+                found.push(rep);
+            },
+            None => addr += 1,
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn insts(src: &str) -> Vec<Instruction> {
+        src.split(';').map(|s| match s.trim().parse() {
+            Ok(ProgramItem::Instr(inst)) => inst,
+            _ => panic!(),
+        }).collect()
+    }
+
+    #[test]
+    fn superopt() {
+        let opts = Options::default();
+        assert_eq!(shorter_equivalent(&insts("addi 1 2 1; addi 1 3 1"), &opts), Some(insts("addi 1 5 1")));
+        assert_eq!(shorter_equivalent(&insts("seti 0 0 2; addr 1 2 1"), &opts), Some(insts("seti 0 0 2")));
+        assert_eq!(shorter_equivalent(&insts("gtri 3 5 4; eqri 4 0 4"), &opts), Some(insts("gtir 6 3 4")));
+        assert_eq!(shorter_equivalent(&insts("muli 1 1 1"), &opts), Some(vec![]));
+        assert_eq!(shorter_equivalent(&insts("mulr 1 1 2; addr 2 2 2"), &opts), None);
+        assert_eq!(shorter_equivalent(&insts("bani 1 255 1; bani 1 15 1; eqri 1 3 2"), &opts), Some(insts("bani 1 15 1; eqri 1 3 2")));
+
+        let program: Vec<ProgramItem> = "#ip 3\nseti 4 0 1\naddi 1 1 1\naddr 1 3 3\nmuli 2 1 2"
+            .lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(scan(&program, 3, &opts), vec![
+            Replacement { addr: 0, original: insts("seti 4 0 1; addi 1 1 1"), replacement: insts("seti 5 0 1") },
+            Replacement { addr: 3, original: insts("muli 2 1 2"), replacement: vec![] },
+        ]);
+    }
+}
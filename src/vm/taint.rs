@@ -0,0 +1,108 @@
+//! Dynamic taint tracking. Attach a `Taint` to a VM with `VM::with_observer`
+//! and each register carries the set of sources its value was computed
+//! from, along with the addresses of the instructions each source flowed
+//! through to get there.
+//!
+//! Since the ip is an ordinary register, "does the halt decision depend on
+//! r0?" is `taint.path(vm.ip, Source::Reg(0))` once the VM halts. Only data
+//! flow is tracked: a value written by `seti` on a path that was chosen by a
+//! tainted comparison is itself untainted.
+
+use std::collections::{BTreeMap, BTreeSet};
+use super::{Instruction, Observer, NREGS, OPERATIONS};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Source {
+    /// The value a register held when the run started.
+    Reg(usize),
+    /// An immediate operand of the instruction at this address.
+    Imm(usize),
+}
+
+type Paths = BTreeMap<Source, BTreeSet<usize>>;
+
+pub struct Taint {
+    regs: [Paths; NREGS],
+}
+impl Default for Taint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Taint {
+    /// Starts with each register tainted only by its own initial value.
+    pub fn new() -> Self {
+        Self {
+            regs: std::array::from_fn(|r| Paths::from([(Source::Reg(r), BTreeSet::new())])),
+        }
+    }
+    pub fn sources(&self, reg: usize) -> impl Iterator<Item = Source> + '_ {
+        self.regs[reg].keys().copied()
+    }
+    pub fn depends_on(&self, reg: usize, src: Source) -> bool {
+        self.regs[reg].contains_key(&src)
+    }
+    /// Addresses of the instructions through which `src` reached `reg`, or
+    /// `None` if the register doesn't depend on it.
+    pub fn path(&self, reg: usize, src: Source) -> Option<&BTreeSet<usize>> {
+        self.regs[reg].get(&src)
+    }
+}
+
+impl Observer for Taint {
+    fn before_instruction(&mut self, ip: usize, inst: &Instruction, _regs: &[usize; NREGS]) {
+        let op = &OPERATIONS[&inst.opcode];
+        let mut result = Paths::new();
+        let mut operand = |immed: bool, v: usize| {
+            if immed {
+                result.entry(Source::Imm(ip)).or_default();
+            }
+            else {
+                for (src, path) in &self.regs[v] {
+                    result.entry(*src).or_default().extend(path);
+                }
+            }
+        };
+        operand(op.a_immed, inst.a);
+        // setr and seti only look at A
+        if !inst.opcode.starts_with("set") {
+            operand(op.b_immed, inst.b);
+        }
+        result.values_mut().for_each(|path| { path.insert(ip); });
+        self.regs[inst.c] = result;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vm::{ProgramItem, RunResult, VM};
+
+    fn run(r0: usize) -> VM<Taint> {
+        let prog: Vec<ProgramItem> = "#ip 3\nseti 7 0 1\neqrr 1 0 2\naddr 2 3 3\nseti 9 0 3"
+            .lines().map(|l| l.parse().unwrap()).collect();
+        let mut vm = VM::with_observer(Taint::new());
+        vm.load(&prog);
+        vm.r[0] = r0;
+        assert!(matches!(vm.run(), RunResult::Halt));
+        vm
+    }
+
+    #[test]
+    fn taint() {
+        let vm = run(7);
+        let taint = vm.observer();
+        assert_eq!(taint.sources(3).collect::<Vec<_>>(),
+            vec![Source::Reg(0), Source::Reg(3), Source::Imm(0)]);
+        assert_eq!(taint.path(3, Source::Reg(0)), Some(&BTreeSet::from([1, 2])));
+        assert_eq!(taint.path(3, Source::Imm(0)), Some(&BTreeSet::from([0, 1, 2])));
+        assert_eq!(taint.path(2, Source::Reg(0)), Some(&BTreeSet::from([1])));
+        assert!(taint.depends_on(0, Source::Reg(0)));
+        assert!(!taint.depends_on(1, Source::Reg(1)));
+
+        // the other branch halts via an immediate, which carries no taint
+        let vm = run(5);
+        assert_eq!(vm.observer().sources(3).collect::<Vec<_>>(), vec![Source::Imm(3)]);
+    }
+}
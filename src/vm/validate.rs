@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::vec::Vec;
+use super::{Instruction, Meta, ProgramItem, NREGS, OPERATIONS};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem found by `validate`. `line` is the 1-based position of the
+/// offending item in the program slice, which is its source line when the
+/// program came straight from `read_input`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+impl Diagnostic {
+    fn error(line: usize, message: String) -> Self {
+        Self { line, severity: Severity::Error, message }
+    }
+    fn warning(line: usize, message: String) -> Self {
+        Self { line, severity: Severity::Warning, message }
+    }
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sev = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "line {}: {}: {}", self.line, sev, self.message)
+    }
+}
+
+/// Checks a program without running it. Errors are things that would make
+/// the VM panic or misbehave; warnings are things that are legal but almost
+/// certainly not what was meant. The result is sorted by line.
+pub fn validate(program: &[ProgramItem]) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let mut ip_reg: Option<usize> = None;
+    let mut ip_line = 0;
+    let mut instrs: Vec<(usize, Instruction)> = Vec::new();
+    let mut labels: HashMap<&str, (usize, usize)> = HashMap::new();
+    let mut entry: Option<(usize, &str)> = None;
+
+    for (idx, item) in program.iter().enumerate() {
+        let line = idx + 1;
+        match item {
+            ProgramItem::Meta(Meta::MapIp(reg)) => {
+                if *reg >= NREGS {
+                    diags.push(Diagnostic::error(line,
+                        format!("#ip register {reg} is out of range (0..{NREGS})")));
+                }
+                if ip_reg.is_some() {
+                    diags.push(Diagnostic::error(line,
+                        format!("#ip declared again (previously on line {ip_line}); only the last one takes effect")));
+                }
+                if !instrs.is_empty() {
+                    diags.push(Diagnostic::warning(line,
+                        "#ip appears after instructions; it still applies to the whole program".to_owned()));
+                }
+                ip_reg = Some(*reg);
+                ip_line = line;
+            },
+            ProgramItem::Meta(Meta::SetReg(reg, _)) |
+            ProgramItem::Meta(Meta::Expect(reg, _)) |
+            ProgramItem::Meta(Meta::Break(reg)) => {
+                if *reg >= NREGS {
+                    diags.push(Diagnostic::error(line,
+                        format!("register {reg} is out of range (0..{NREGS})")));
+                }
+            },
+            ProgramItem::Meta(Meta::Label(name)) => {
+                if let Some((prev, _)) = labels.get(name.as_str()) {
+                    diags.push(Diagnostic::error(line,
+                        format!("label {name} already defined on line {prev}")));
+                }
+                else {
+                    labels.insert(name, (line, instrs.len()));
+                }
+            },
+            ProgramItem::Meta(Meta::Entry(name)) => {
+                if let Some((prev, _)) = entry {
+                    diags.push(Diagnostic::error(line,
+                        format!("#entry declared again (previously on line {prev}); only the last one takes effect")));
+                }
+                entry = Some((line, name));
+            },
+            ProgramItem::Meta(Meta::Fuel(_)) |
+            ProgramItem::Meta(Meta::ExpectBreak(_)) => (),
+            ProgramItem::Instr(inst) => {
+                let op = &OPERATIONS[&inst.opcode];
+                if !op.a_immed && inst.a >= NREGS {
+                    diags.push(Diagnostic::error(line,
+                        format!("{}: register A ({}) is out of range (0..{NREGS})", inst.opcode, inst.a)));
+                }
+                if !op.b_immed && inst.b >= NREGS {
+                    diags.push(Diagnostic::error(line,
+                        format!("{}: register B ({}) is out of range (0..{NREGS})", inst.opcode, inst.b)));
+                }
+                if inst.c >= NREGS {
+                    diags.push(Diagnostic::error(line,
+                        format!("{}: register C ({}) is out of range (0..{NREGS})", inst.opcode, inst.c)));
+                }
+                instrs.push((line, *inst));
+            },
+        }
+    }
+
+    let start = match entry {
+        None => Some(0),
+        Some((line, name)) => match labels.get(name) {
+            Some((_, addr)) => Some(*addr),
+            None => {
+                diags.push(Diagnostic::error(line, format!("entry label {name} is not defined")));
+                None
+            },
+        },
+    };
+
+    // the VM binds register 0 if there's no #ip
+    let ip_reg = ip_reg.unwrap_or(0);
+    if let Some(start) = start.filter(|_| ip_reg < NREGS) {
+        check_flow(&instrs, ip_reg, start, &mut diags);
+    }
+    diags.sort_by_key(|d| d.line);
+    diags
+}
+
+// Abstract register value for the control flow analysis: either a small set
+// of possible values, or anything at all.
+#[derive(Clone, Debug, PartialEq)]
+enum Val {
+    Known(Vec<usize>),
+    Any,
+}
+
+const MAX_KNOWN: usize = 8;
+
+impl Val {
+    fn one(v: usize) -> Self {
+        Val::Known(vec![v])
+    }
+    fn from_vec(mut v: Vec<usize>) -> Self {
+        v.sort_unstable();
+        v.dedup();
+        if v.len() > MAX_KNOWN { Val::Any } else { Val::Known(v) }
+    }
+    fn join(&self, other: &Val) -> Val {
+        match (self, other) {
+            (Val::Known(a), Val::Known(b)) =>
+                Val::from_vec(a.iter().chain(b.iter()).copied().collect()),
+            _ => Val::Any,
+        }
+    }
+    fn apply(opcode: &str, a: &Val, b: &Val) -> Val {
+        let op = &OPERATIONS[opcode];
+        // keep operands small enough that no op can overflow
+        let limit = 1usize << (usize::BITS / 2);
+        match (a, b) {
+            (Val::Known(a), Val::Known(b))
+                if a.iter().chain(b.iter()).all(|v| *v < limit) =>
+                Val::from_vec(a.iter()
+                    .flat_map(|x| b.iter().map(|y| (op.op)(*x, *y)))
+                    .collect()),
+            _ if opcode.starts_with("gt") || opcode.starts_with("eq") => Val::from_vec(vec![0, 1]),
+            _ => Val::Any,
+        }
+    }
+}
+
+type State = [Val; NREGS];
+
+fn read(state: &State, immed: bool, v: usize) -> Val {
+    if immed { Val::one(v) }
+    else if v < NREGS { state[v].clone() }
+    else { Val::Any }
+}
+
+// Forward dataflow over the instructions, starting at the entry point with
+// every register unknown, to find which instructions can run and where jumps go.
+fn check_flow(instrs: &[(usize, Instruction)], ip_reg: usize, start: usize, diags: &mut Vec<Diagnostic>) {
+    let len = instrs.len();
+    if start >= len {
+        return;
+    }
+    let mut states: Vec<Option<State>> = vec![None; len];
+    let mut worklist: Vec<usize> = vec![start];
+    states[start] = Some(std::array::from_fn(|_| Val::Any));
+    let mut leaves: Vec<Option<Vec<usize>>> = vec![None; len];
+
+    while let Some(addr) = worklist.pop() {
+        let mut state = states[addr].clone().unwrap();
+        state[ip_reg] = Val::one(addr);
+        let inst = &instrs[addr].1;
+        let op = &OPERATIONS[&inst.opcode];
+        let result = Val::apply(inst.opcode,
+            &read(&state, op.a_immed, inst.a),
+            &read(&state, op.b_immed, inst.b));
+        if inst.c < NREGS {
+            state[inst.c] = result.clone();
+        }
+        let targets: Vec<usize> = if inst.c == ip_reg {
+            match &result {
+                Val::Known(vals) => {
+                    let targets: Vec<usize> = vals.iter().map(|v| v.saturating_add(1)).collect();
+                    // a later visit may have merged in targets that stay
+                    leaves[addr] = if targets.iter().all(|t| *t >= len) { Some(targets.clone()) } else { None };
+                    targets
+                },
+                Val::Any => {
+                    leaves[addr] = None;
+                    (0..len).collect()
+                },
+            }
+        }
+        else {
+            vec![addr + 1]
+        };
+        for t in targets.into_iter().filter(|t| *t < len) {
+            let mut next = state.clone();
+            next[ip_reg] = Val::one(t);
+            let merged = match &states[t] {
+                None => next,
+                Some(prev) => std::array::from_fn(|r| prev[r].join(&next[r])),
+            };
+            if states[t].as_ref() != Some(&merged) {
+                states[t] = Some(merged);
+                worklist.push(t);
+            }
+        }
+    }
+
+    for (addr, targets) in leaves.iter().enumerate() {
+        if let Some(targets) = targets {
+            let list = targets.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ");
+            diags.push(Diagnostic::warning(instrs[addr].0,
+                format!("jump always leaves the program (to address {list})")));
+        }
+    }
+
+    let mut addr = 0;
+    while addr < len {
+        if states[addr].is_none() {
+            let start = addr;
+            while addr < len && states[addr].is_none() {
+                addr += 1;
+            }
+            let msg = if addr - start == 1 {
+                format!("unreachable instruction at address {start}")
+            } else {
+                format!("unreachable instructions at addresses {start}..{}", addr - 1)
+            };
+            diags.push(Diagnostic::warning(instrs[start].0, msg));
+        }
+        addr += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn program(src: &str) -> Vec<ProgramItem> {
+        src.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn validate_program() {
+        let prog = program("#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\nsetr 1 0 0\nseti 8 0 4\nseti 9 0 5");
+        let diags = validate(&prog);
+        assert_eq!(diags, vec![
+            Diagnostic::warning(5, "unreachable instruction at address 3".to_owned()),
+            Diagnostic::warning(7, "unreachable instruction at address 5".to_owned()),
+        ]);
+
+        // a conditional jump via a comparison result keeps both paths live
+        let prog = program("#ip 5\neqri 0 1 3\naddr 3 5 5\naddi 1 1 1\nseti 99 0 5\naddr 2 7 1");
+        let diags = validate(&prog);
+        assert_eq!(diags, vec![
+            Diagnostic::warning(5, "jump always leaves the program (to address 100)".to_owned()),
+            Diagnostic::error(6, "addr: register B (7) is out of range (0..6)".to_owned()),
+            Diagnostic::warning(6, "unreachable instruction at address 4".to_owned()),
+        ]);
+
+        // the first visit to address 4 only sees r1 = 20, the second also 1
+        let prog = program("#ip 5\nseti 20 0 1\neqri 0 1 3\naddr 3 5 5\nseti 1 0 1\nsetr 1 0 5");
+        assert_eq!(validate(&prog), vec![]);
+
+        let prog = program("#ip 1\nseti 0 0 0\n#ip 9");
+        let diags = validate(&prog);
+        assert_eq!(diags, vec![
+            Diagnostic::error(3, "#ip register 9 is out of range (0..6)".to_owned()),
+            Diagnostic::error(3, "#ip declared again (previously on line 1); only the last one takes effect".to_owned()),
+            Diagnostic::warning(3, "#ip appears after instructions; it still applies to the whole program".to_owned()),
+        ]);
+
+        let prog = program("#ip 1\n#reg 6 1\n#label top\nseti 0 0 0\n#label top\n#entry top\n#entry bottom");
+        let diags = validate(&prog);
+        assert_eq!(diags, vec![
+            Diagnostic::error(2, "register 6 is out of range (0..6)".to_owned()),
+            Diagnostic::error(5, "label top already defined on line 3".to_owned()),
+            Diagnostic::error(7, "#entry declared again (previously on line 6); only the last one takes effect".to_owned()),
+            Diagnostic::error(7, "entry label bottom is not defined".to_owned()),
+        ]);
+    }
+}